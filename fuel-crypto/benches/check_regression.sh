@@ -0,0 +1,55 @@
+#!/usr/bin/env bash
+
+# Compares the `fuel-crypto` hasher benchmarks against the baseline stored in
+# `fuel-crypto/benches/baseline` and fails if the mean time of any benchmark
+# regressed by more than 10%.
+#
+# The stored numbers are only meaningful on the machine that produced them, so
+# refresh them on the CI runner whenever it changes:
+#   ./fuel-crypto/benches/check_regression.sh save
+# and compare the current tree against them with:
+#   ./fuel-crypto/benches/check_regression.sh
+#
+# Requires `jq`. The threshold can be changed with `THRESHOLD` (a fraction).
+
+set -euo pipefail
+
+THRESHOLD="${THRESHOLD:-0.10}"
+BASELINE="stored"
+STORE="fuel-crypto/benches/baseline"
+CRITERION="target/criterion"
+
+cd "$(dirname "$0")/../.."
+
+bench() {
+    cargo bench -p fuel-crypto --bench hasher -- "$@"
+}
+
+if [ "${1:-check}" = "save" ]; then
+    bench --save-baseline "$BASELINE"
+    rm -rf "$STORE"/hasher-*
+    (cd "$CRITERION" && find hasher-* -type d -name "$BASELINE" -print0 \
+        | xargs -0 -I{} cp -r --parents {} "$OLDPWD/$STORE")
+    exit 0
+fi
+
+if ! compgen -G "$STORE/hasher-*" > /dev/null; then
+    echo "No stored baseline in $STORE, run \`$0 save\` first"
+    exit 1
+fi
+
+mkdir -p "$CRITERION"
+cp -r "$STORE"/hasher-* "$CRITERION"
+bench --baseline "$BASELINE"
+
+status=0
+while IFS= read -r -d '' estimates; do
+    change=$(jq '.mean.point_estimate' "$estimates")
+    if awk -v change="$change" -v threshold="$THRESHOLD" 'BEGIN { exit !(change > threshold) }'; then
+        benchmark=$(dirname "$(dirname "$estimates")")
+        echo "Regression in ${benchmark#"$CRITERION"/}: mean changed by ${change}"
+        status=1
+    fi
+done < <(find "$CRITERION" -path "$CRITERION/hasher-*/change/estimates.json" -print0)
+
+exit $status
@@ -0,0 +1,81 @@
+use criterion::{
+    BenchmarkId,
+    Criterion,
+    Throughput,
+    black_box,
+    criterion_group,
+    criterion_main,
+};
+use fuel_crypto::{
+    Hasher,
+    blake3_cpu_features,
+};
+use rand::{
+    RngCore,
+    SeedableRng,
+    rngs::StdRng,
+};
+
+const KEY: [u8; 32] = [0xa5; 32];
+const CONTEXT: &str = "fuel-crypto 2024-01-01 hasher benchmark";
+
+fn random_bytes(rng: &mut StdRng, size: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; size];
+    rng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn hasher(c: &mut Criterion) {
+    // Report the SIMD extensions in use, as they dominate the hashing throughput
+    println!("BLAKE3 CPU features: {:?}", blake3_cpu_features());
+
+    let rng = &mut StdRng::seed_from_u64(8586);
+
+    let mut group = c.benchmark_group("hasher-hash");
+    for (name, size) in [
+        ("64B", 64),
+        ("1KB", 1 << 10),
+        ("64KB", 64 << 10),
+        ("1MB", 1 << 20),
+        ("10MB", 10 << 20),
+    ] {
+        let data = random_bytes(rng, size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &data, |b, data| {
+            b.iter(|| Hasher::hash(black_box(data)))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("hasher-hash-many");
+    for count in [100, 10_000] {
+        let inputs = (0..count)
+            .map(|_| random_bytes(rng, 64))
+            .collect::<Vec<_>>();
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &inputs,
+            |b, inputs| b.iter(|| Hasher::hash_many(black_box(inputs)).for_each(drop)),
+        );
+    }
+    group.finish();
+
+    let data = random_bytes(rng, 1 << 10);
+    let mut group = c.benchmark_group("hasher-modes");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.bench_function("keyed", |b| {
+        b.iter(|| Hasher::new_keyed(black_box(&KEY)).chain(&data).finalize())
+    });
+    group.bench_function("derive-key", |b| {
+        b.iter(|| {
+            Hasher::new_derive_key(black_box(CONTEXT))
+                .chain(&data)
+                .finalize()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, hasher);
+criterion_main!(benches);
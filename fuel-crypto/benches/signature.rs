@@ -4,7 +4,7 @@ use criterion::{
     criterion_group,
     criterion_main,
 };
-use sha2::digest::Update;
+use k256::sha2::digest::Update;
 
 fn signatures(c: &mut Criterion) {
     let message = b"New opinions are always suspected, and usually opposed, without any other reason but because they are not common.";
@@ -99,14 +99,16 @@ fn signatures(c: &mut Criterion) {
 
     // k256
     let (k2_key, k2_verifying, k2_digest, k2_signature, k2_recovery_id) = {
-        use k256::ecdsa::{
-            SigningKey,
-            VerifyingKey,
-            signature::DigestVerifier,
-        };
-        use sha2::{
-            Digest,
-            Sha256,
+        use k256::{
+            ecdsa::{
+                SigningKey,
+                VerifyingKey,
+                signature::DigestVerifier,
+            },
+            sha2::{
+                Digest,
+                Sha256,
+            },
         };
 
         let digest = Sha256::new().chain(message);
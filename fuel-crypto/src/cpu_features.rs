@@ -0,0 +1,60 @@
+//! Runtime detection of the SIMD extensions BLAKE3 can use
+
+/// CPU extensions available to the BLAKE3 implementation on this machine.
+///
+/// With the `std` feature the extensions are detected at runtime; without it
+/// only the extensions enabled at compile time are reported.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Blake3CpuFeatures {
+    /// SSE4.1 is available (x86 and x86_64).
+    pub sse41: bool,
+    /// AVX2 is available (x86 and x86_64).
+    pub avx2: bool,
+    /// AVX-512F and AVX-512VL are available (x86 and x86_64).
+    pub avx512: bool,
+    /// NEON is available (aarch64).
+    pub neon: bool,
+}
+
+/// Detect the CPU extensions BLAKE3 can use for hashing on this machine.
+///
+/// Useful to confirm that a deployment benefits from hardware acceleration.
+pub fn blake3_cpu_features() -> Blake3CpuFeatures {
+    let (sse41, avx2, avx512) = detect_x86();
+
+    Blake3CpuFeatures {
+        sse41,
+        avx2,
+        avx512,
+        neon: detect_neon(),
+    }
+}
+
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+fn detect_x86() -> (bool, bool, bool) {
+    (
+        std::is_x86_feature_detected!("sse4.1"),
+        std::is_x86_feature_detected!("avx2"),
+        std::is_x86_feature_detected!("avx512f")
+            && std::is_x86_feature_detected!("avx512vl"),
+    )
+}
+
+#[cfg(not(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64"))))]
+fn detect_x86() -> (bool, bool, bool) {
+    (
+        cfg!(target_feature = "sse4.1"),
+        cfg!(target_feature = "avx2"),
+        cfg!(all(target_feature = "avx512f", target_feature = "avx512vl")),
+    )
+}
+
+#[cfg(all(feature = "std", target_arch = "aarch64"))]
+fn detect_neon() -> bool {
+    std::arch::is_aarch64_feature_detected!("neon")
+}
+
+#[cfg(not(all(feature = "std", target_arch = "aarch64")))]
+fn detect_neon() -> bool {
+    cfg!(all(target_arch = "aarch64", target_feature = "neon"))
+}
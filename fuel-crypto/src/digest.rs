@@ -0,0 +1,100 @@
+use crate::Hasher;
+use core::{
+    fmt,
+    ops::Deref,
+};
+use fuel_types::Bytes32;
+use zeroize::Zeroize;
+
+/// Output of a [`Hasher`]
+///
+/// Unlike [`Bytes32`], which also holds keys, addresses and other 32-byte
+/// values, a `Digest` can only be obtained by hashing, so it can't be confused
+/// with any of them.
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Zeroize)]
+#[repr(transparent)]
+pub struct Digest([u8; Digest::LEN]);
+
+impl Digest {
+    /// Memory length of the type in bytes.
+    pub const LEN: usize = Bytes32::LEN;
+
+    /// Hash the provided data, returning its digest
+    pub fn new<B>(data: B) -> Self
+    where
+        B: AsRef<[u8]>,
+    {
+        Hasher::hash(data)
+    }
+
+    pub(crate) const fn from_bytes(bytes: [u8; Self::LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Consume the digest, returning its bytes
+    pub fn into_bytes(self) -> [u8; Self::LEN] {
+        self.0
+    }
+}
+
+impl Deref for Digest {
+    type Target = [u8; Digest::LEN];
+
+    fn deref(&self) -> &[u8; Digest::LEN] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Digest> for [u8; Digest::LEN] {
+    fn from(digest: Digest) -> [u8; Digest::LEN] {
+        digest.0
+    }
+}
+
+impl From<Digest> for Bytes32 {
+    fn from(digest: Digest) -> Self {
+        digest.0.into()
+    }
+}
+
+impl From<&Hasher> for Digest {
+    fn from(hasher: &Hasher) -> Self {
+        hasher.digest()
+    }
+}
+
+impl From<Hasher> for Digest {
+    fn from(hasher: Hasher) -> Self {
+        hasher.finalize()
+    }
+}
+
+impl fmt::LowerHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&Bytes32::from(self.0), f)
+    }
+}
+
+impl fmt::UpperHex for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&Bytes32::from(self.0), f)
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&Bytes32::from(self.0), f)
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&Bytes32::from(self.0), f)
+    }
+}
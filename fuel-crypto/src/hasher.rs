@@ -1,7 +1,11 @@
+use crate::{
+    Digest,
+    OutputReader,
+};
 use fuel_types::Bytes32;
-use blake3;
 
 use core::iter;
+use subtle::ConstantTimeEq;
 
 /// Standard hasher
 #[derive(Debug, Default, Clone)]
@@ -11,15 +15,39 @@ impl Hasher {
     /// Length of the output
     pub const OUTPUT_LEN: usize = Bytes32::LEN;
 
+    /// Create a hasher in BLAKE3 keyed mode, producing a MAC of the input
+    /// under `key`
+    pub fn new_keyed(key: &[u8; 32]) -> Self {
+        Self(blake3::Hasher::new_keyed(key))
+    }
+
+    /// Create a hasher in BLAKE3 key derivation mode for the given `context`
+    ///
+    /// The context string should be hardcoded, globally unique and
+    /// application-specific.
+    pub fn new_derive_key(context: &str) -> Self {
+        Self(blake3::Hasher::new_derive_key(context))
+    }
+
     /// Append data to the hasher
-    pub fn input<B>(&mut self, data: B)
+    pub fn update<B>(&mut self, data: B)
     where
         B: AsRef<[u8]>,
     {
         self.0.update(data.as_ref());
     }
 
+    /// Append data to the hasher
+    #[deprecated(since = "0.66.0", note = "Use `update` instead")]
+    pub fn input<B>(&mut self, data: B)
+    where
+        B: AsRef<[u8]>,
+    {
+        self.update(data)
+    }
+
     /// Consume, append data and return the hasher
+    #[must_use = "chain() consumes the hasher and returns the updated one"]
     pub fn chain<B>(mut self, data: B) -> Self
     where
         B: AsRef<[u8]>,
@@ -28,7 +56,22 @@ impl Hasher {
         self
     }
 
+    /// Append all the given byte slices to the hasher
+    pub fn input_all(&mut self, items: &[&[u8]]) {
+        for item in items {
+            self.0.update(item);
+        }
+    }
+
+    /// Consume, append all the given byte slices and return the hasher
+    #[must_use = "chain_all() consumes the hasher and returns the updated one"]
+    pub fn chain_all(mut self, items: &[&[u8]]) -> Self {
+        self.input_all(items);
+        self
+    }
+
     /// Consume, append the items of the iterator and return the hasher
+    #[must_use = "extend_chain() consumes the hasher and returns the updated one"]
     pub fn extend_chain<B, I>(mut self, iter: I) -> Self
     where
         B: AsRef<[u8]>,
@@ -39,30 +82,213 @@ impl Hasher {
         self
     }
 
-    /// Reset the hasher to the default state
+    /// Append `v` to the hasher as a 8-byte big-endian integer
+    pub fn absorb_u64(&mut self, v: u64) {
+        self.0.update(&v.to_be_bytes());
+    }
+
+    /// Append `v` to the hasher as a 4-byte big-endian integer
+    pub fn absorb_u32(&mut self, v: u32) {
+        self.0.update(&v.to_be_bytes());
+    }
+
+    /// Append `v` to the hasher as a 2-byte big-endian integer
+    pub fn absorb_u16(&mut self, v: u16) {
+        self.0.update(&v.to_be_bytes());
+    }
+
+    /// Append the byte `v` to the hasher
+    pub fn absorb_u8(&mut self, v: u8) {
+        self.0.update(&[v]);
+    }
+
+    /// Consume, append `v` as a 8-byte big-endian integer and return the hasher
+    #[must_use = "chain_u64() consumes the hasher and returns the updated one"]
+    pub fn chain_u64(mut self, v: u64) -> Self {
+        self.absorb_u64(v);
+        self
+    }
+
+    /// Consume, append `v` as a 4-byte big-endian integer and return the hasher
+    #[must_use = "chain_u32() consumes the hasher and returns the updated one"]
+    pub fn chain_u32(mut self, v: u32) -> Self {
+        self.absorb_u32(v);
+        self
+    }
+
+    /// Consume, append `v` as a 2-byte big-endian integer and return the hasher
+    #[must_use = "chain_u16() consumes the hasher and returns the updated one"]
+    pub fn chain_u16(mut self, v: u16) -> Self {
+        self.absorb_u16(v);
+        self
+    }
+
+    /// Consume, append the byte `v` and return the hasher
+    #[must_use = "chain_u8() consumes the hasher and returns the updated one"]
+    pub fn chain_u8(mut self, v: u8) -> Self {
+        self.absorb_u8(v);
+        self
+    }
+
+    /// Reset the hasher to its initial state, keeping the key of a keyed
+    /// hasher
     pub fn reset(&mut self) {
-        self.0 = blake3::Hasher::new();
+        self.0.reset();
     }
 
     /// Hash the provided data, returning its digest
-    pub fn hash<B>(data: B) -> Bytes32
+    #[must_use = "calling hash() without using the result is a likely bug"]
+    pub fn hash<B>(data: B) -> Digest
     where
         B: AsRef<[u8]>,
     {
         let hash = blake3::hash(data.as_ref());
-        (*hash.as_bytes()).into()
+        Digest::from_bytes(*hash.as_bytes())
+    }
+
+    /// Hash each of the provided inputs independently, yielding their digests
+    /// in order
+    ///
+    /// This is a convenience over calling [`Hasher::hash`] on each input, not a
+    /// batched implementation: the `blake3` crate doesn't expose hashing
+    /// independent inputs in parallel, so they are hashed one after the other,
+    /// and inputs shorter than a 1 KiB chunk don't benefit from SIMD.
+    pub fn hash_many<I, B>(inputs: I) -> impl Iterator<Item = Digest>
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        inputs.into_iter().map(Self::hash)
+    }
+
+    /// Hash `data` prefixed with `nonce` as an 8-byte big-endian integer
+    ///
+    /// Committing to the same data under different nonces yields unrelated
+    /// digests, so the commitments can't be linked to each other.
+    #[must_use = "calling hash_with_nonce() without using the result is a likely bug"]
+    pub fn hash_with_nonce(data: &[u8], nonce: u64) -> Digest {
+        Self::default()
+            .chain(nonce.to_be_bytes())
+            .chain(data)
+            .finalize()
+    }
+
+    /// Hash the provided fields, prefixing each with its length
+    ///
+    /// Every field is preceded by its length as a 4-byte big-endian integer, so
+    /// different partitionings of the same bytes produce different digests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a field is longer than `u32::MAX` bytes.
+    #[must_use = "calling hash_fields() without using the result is a likely bug"]
+    pub fn hash_fields(fields: &[&[u8]]) -> Digest {
+        Self::hash_with_length_prefixes(fields, |len| {
+            u32::try_from(len)
+                .expect("field length must fit in 4 bytes")
+                .to_be_bytes()
+        })
+    }
+
+    /// Hash the provided parts, prefixing each with its length as an 8-byte
+    /// big-endian integer
+    ///
+    /// Like [`Hasher::hash_fields`], different partitionings of the same bytes
+    /// produce different digests, but parts of any length are accepted. An
+    /// empty list of parts hashes to the digest of the empty input.
+    #[must_use = "calling hash_length_prefixed() without using the result is a likely bug"]
+    pub fn hash_length_prefixed(parts: &[&[u8]]) -> Digest {
+        Self::hash_with_length_prefixes(parts, |len| {
+            u64::try_from(len)
+                .expect("part length must fit in 8 bytes")
+                .to_be_bytes()
+        })
+    }
+
+    /// Hash the provided fields, each preceded by the encoding of its length
+    /// returned by `prefix`
+    fn hash_with_length_prefixes<F, P>(fields: &[&[u8]], prefix: F) -> Digest
+    where
+        F: Fn(usize) -> P,
+        P: AsRef<[u8]>,
+    {
+        let mut hasher = Self::default();
+        for field in fields {
+            hasher.update(prefix(field.len()));
+            hasher.update(field);
+        }
+        hasher.finalize()
+    }
+
+    /// Commit to an ordered list of elements
+    ///
+    /// The digest covers the number of elements as an 8-byte big-endian
+    /// integer, followed by the elements themselves, so lists of different
+    /// lengths never collide by concatenating to the same bytes. Element
+    /// boundaries are not encoded; use [`Hasher::hash_fields`] for lists of
+    /// variable-size elements.
+    ///
+    /// This is the same commitment as [`Hasher::compute_commitment`].
+    #[cfg(feature = "alloc")]
+    #[must_use = "calling commit_vector() without using the result is a likely bug"]
+    pub fn commit_vector<I>(elements: I) -> Digest
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        Self::compute_commitment(elements)
+    }
+
+    /// Commit to an ordered list of elements, binding the element count as an
+    /// 8-byte big-endian integer
+    ///
+    /// The count is hashed before the elements, so they are collected to
+    /// count them.
+    #[cfg(feature = "alloc")]
+    #[must_use = "calling compute_commitment() without using the result is a likely bug"]
+    pub fn compute_commitment<I>(elements: I) -> Digest
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let elements = elements.into_iter().collect::<alloc::vec::Vec<_>>();
+        let count =
+            u64::try_from(elements.len()).expect("element count must fit in 8 bytes");
+        Self::default()
+            .chain(count.to_be_bytes())
+            .extend_chain(elements)
+            .finalize()
     }
 
     /// Consume the hasher, returning the digest
-    pub fn finalize(self) -> Bytes32 {
+    #[must_use = "calling finalize() without using the result is a likely bug"]
+    pub fn finalize(self) -> Digest {
         let hash = self.0.finalize();
-        (*hash.as_bytes()).into()
+        Digest::from_bytes(*hash.as_bytes())
+    }
+
+    /// Consume the hasher, returning a reader of its extendable output
+    ///
+    /// The first 32 bytes read are the digest returned by [`Hasher::finalize`].
+    pub fn finalize_xof(self) -> OutputReader {
+        OutputReader::new(self.0.finalize_xof())
+    }
+
+    /// Check that `expected_tag` is the keyed hash of `data` under `key`
+    ///
+    /// The tags are compared in constant time, so the result doesn't leak how
+    /// many leading bytes of `expected_tag` are correct.
+    #[must_use = "calling verify_mac() without using the result is a likely bug"]
+    pub fn verify_mac(key: &[u8; 32], data: &[u8], expected_tag: &Bytes32) -> bool {
+        let tag = Self::new_keyed(key).chain(data).finalize();
+        tag.as_ref().ct_eq(expected_tag.as_ref()).into()
     }
 
     /// Return the digest without consuming the hasher
-    pub fn digest(&self) -> Bytes32 {
+    #[must_use = "calling digest() without using the result is a likely bug"]
+    pub fn digest(&self) -> Digest {
         let hash = self.0.clone().finalize();
-        (*hash.as_bytes()).into()
+        Digest::from_bytes(*hash.as_bytes())
     }
 }
 
@@ -83,6 +309,48 @@ where
     B: AsRef<[u8]>,
 {
     fn extend<T: IntoIterator<Item = B>>(&mut self, iter: T) {
-        iter.into_iter().for_each(|b| self.input(b))
+        iter.into_iter().for_each(|b| self.update(b))
     }
 }
+
+/// Build a [`Hasher`] by chaining all the provided inputs.
+///
+/// `hash_chain!(a, b, c)` expands to `Hasher::default().chain(a).chain(b).chain(c)`.
+///
+/// ```
+/// use fuel_crypto::{
+///     Hasher,
+///     hash_chain,
+/// };
+///
+/// let digest = hash_chain!(b"a", b"b", b"c").finalize();
+/// assert_eq!(digest, Hasher::hash(b"abc"));
+/// ```
+#[macro_export]
+macro_rules! hash_chain {
+    ($($item:expr),* $(,)?) => {
+        $crate::Hasher::default()$(.chain($item))*
+    };
+}
+
+/// Hash the concatenation of all the provided inputs, returning the digest.
+///
+/// `blake3_hash!(a, b, c)` expands to [`hash_chain!(a, b, c)`](crate::hash_chain)
+/// followed by `.finalize()`, i.e.
+/// `Hasher::default().chain(a).chain(b).chain(c).finalize()`.
+///
+/// ```
+/// use fuel_crypto::{
+///     Hasher,
+///     blake3_hash,
+/// };
+///
+/// let digest = blake3_hash!(b"a", b"b", b"c");
+/// assert_eq!(digest, Hasher::hash(b"abc"));
+/// ```
+#[macro_export]
+macro_rules! blake3_hash {
+    ($($item:expr),* $(,)?) => {
+        $crate::hash_chain!($($item),*).finalize()
+    };
+}
@@ -0,0 +1,69 @@
+use crate::Digest;
+use fuel_types::Bytes32;
+
+/// Hasher that only uses stack memory, for environments without an allocator.
+///
+/// Exposes the same API as [`Hasher`](crate::Hasher) and produces identical
+/// digests.
+#[derive(Debug, Default, Clone)]
+pub struct HeaplessHasher(blake3::Hasher);
+
+impl HeaplessHasher {
+    /// Length of the output
+    pub const OUTPUT_LEN: usize = Bytes32::LEN;
+
+    /// Append data to the hasher
+    pub fn update<B>(&mut self, data: B)
+    where
+        B: AsRef<[u8]>,
+    {
+        self.0.update(data.as_ref());
+    }
+
+    /// Append data to the hasher
+    #[deprecated(since = "0.66.0", note = "Use `update` instead")]
+    pub fn input<B>(&mut self, data: B)
+    where
+        B: AsRef<[u8]>,
+    {
+        self.update(data)
+    }
+
+    /// Consume, append data and return the hasher
+    #[must_use = "chain() consumes the hasher and returns the updated one"]
+    pub fn chain<B>(mut self, data: B) -> Self
+    where
+        B: AsRef<[u8]>,
+    {
+        self.0.update(data.as_ref());
+        self
+    }
+
+    /// Reset the hasher to the default state
+    pub fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    /// Hash the provided data, returning its digest
+    #[must_use = "calling hash() without using the result is a likely bug"]
+    pub fn hash<B>(data: B) -> Digest
+    where
+        B: AsRef<[u8]>,
+    {
+        let hash = blake3::hash(data.as_ref());
+        Digest::from_bytes(*hash.as_bytes())
+    }
+
+    /// Consume the hasher, returning the digest
+    #[must_use = "calling finalize() without using the result is a likely bug"]
+    pub fn finalize(self) -> Digest {
+        self.digest()
+    }
+
+    /// Return the digest without consuming the hasher
+    #[must_use = "calling digest() without using the result is a likely bug"]
+    pub fn digest(&self) -> Digest {
+        let hash = self.0.finalize();
+        Digest::from_bytes(*hash.as_bytes())
+    }
+}
@@ -0,0 +1,19 @@
+//! Hierarchical key derivation over BLAKE3
+
+/// Derive the child key at `index` from `parent_key`.
+///
+/// The child key is the BLAKE3 keyed hash of the big-endian encoding of `index`,
+/// using `parent_key` as the key.
+pub fn derive_child_key(parent_key: &[u8; 32], index: u32) -> [u8; 32] {
+    let hash = blake3::keyed_hash(parent_key, &index.to_be_bytes());
+    *hash.as_bytes()
+}
+
+/// Derive the key at the end of `path`, starting from `root_key`.
+///
+/// Each element of `path` is a child index applied with [`derive_child_key`] to
+/// the key derived so far. An empty path returns `root_key`.
+pub fn derive_key_path(root_key: &[u8; 32], path: &[u32]) -> [u8; 32] {
+    path.iter()
+        .fold(*root_key, |key, index| derive_child_key(&key, *index))
+}
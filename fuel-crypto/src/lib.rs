@@ -15,6 +15,9 @@
     clippy::string_slice
 )]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 // Unused but needed for version pinning
 use base64ct as _;
 
@@ -38,13 +41,19 @@ pub use fuel_types;
 /// Required export to use randomness features
 pub use rand;
 
+mod cpu_features;
+mod digest;
 mod error;
 mod hasher;
+#[cfg(feature = "heapless")]
+mod heapless_hasher;
 mod message;
 mod mnemonic;
+mod output_reader;
 mod secp256;
 
 pub mod ed25519;
+pub mod kdf;
 
 pub use secp256::backend::r1 as secp256r1;
 
@@ -57,9 +66,17 @@ pub use secp256::{
 #[cfg(test)]
 mod tests;
 
+pub use cpu_features::{
+    Blake3CpuFeatures,
+    blake3_cpu_features,
+};
+pub use digest::Digest;
 pub use error::Error;
 pub use hasher::Hasher;
+#[cfg(feature = "heapless")]
+pub use heapless_hasher::HeaplessHasher;
 pub use message::Message;
+pub use output_reader::OutputReader;
 
 #[cfg(all(feature = "std", feature = "random"))]
 pub use mnemonic::generate_mnemonic_phrase;
@@ -21,7 +21,7 @@ impl Message {
     where
         M: AsRef<[u8]>,
     {
-        Self(Hasher::hash(message))
+        Self(Hasher::hash(message).into())
     }
 
     /// Construct a `Message` directly from its bytes.
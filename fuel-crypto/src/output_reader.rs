@@ -0,0 +1,51 @@
+//! Extendable output of the BLAKE3 hasher
+
+/// Reader of the extendable output (XOF) of a [`Hasher`](crate::Hasher),
+/// returned by [`Hasher::finalize_xof`](crate::Hasher::finalize_xof).
+///
+/// The first 32 bytes of the output are the digest returned by
+/// [`Hasher::finalize`](crate::Hasher::finalize); the output can be read
+/// further, up to 2^64 bytes.
+#[derive(Debug, Clone)]
+pub struct OutputReader(blake3::OutputReader);
+
+impl OutputReader {
+    pub(crate) fn new(reader: blake3::OutputReader) -> Self {
+        Self(reader)
+    }
+
+    /// Fill `buf` with the next bytes of the output
+    pub fn fill(&mut self, buf: &mut [u8]) {
+        self.0.fill(buf)
+    }
+
+    /// The number of bytes of output read so far
+    pub fn position(&self) -> u64 {
+        self.0.position()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Read for OutputReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.fill(buf);
+        Ok(buf.len())
+    }
+}
+
+/// Reading never blocks: the output is computed in place as the buffer is
+/// filled.
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for OutputReader {
+    fn poll_read(
+        self: core::pin::Pin<&mut Self>,
+        _cx: &mut core::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> core::task::Poll<std::io::Result<()>> {
+        let unfilled = buf.initialize_unfilled();
+        let len = unfilled.len();
+        self.get_mut().fill(unfilled);
+        buf.advance(len);
+        core::task::Poll::Ready(Ok(()))
+    }
+}
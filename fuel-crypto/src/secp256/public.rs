@@ -31,7 +31,7 @@ impl PublicKey {
 
     /// Cryptographic hash of the public key.
     pub fn hash(&self) -> Bytes32 {
-        Hasher::hash(self.as_ref())
+        Hasher::hash(self.as_ref()).into()
     }
 }
 
@@ -0,0 +1,24 @@
+use crate::blake3_cpu_features;
+
+#[test]
+fn blake3_cpu_features_match_target_architecture() {
+    let features = blake3_cpu_features();
+
+    if !cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
+        assert!(!features.sse41 && !features.avx2 && !features.avx512);
+    }
+    if !cfg!(target_arch = "aarch64") {
+        assert!(!features.neon);
+    }
+}
+
+#[test]
+fn blake3_cpu_features_include_compile_time_features() {
+    let features = blake3_cpu_features();
+
+    assert!(features.sse41 || !cfg!(target_feature = "sse4.1"));
+    assert!(features.avx2 || !cfg!(target_feature = "avx2"));
+    assert!(
+        features.neon || !cfg!(all(target_arch = "aarch64", target_feature = "neon"))
+    );
+}
@@ -0,0 +1,36 @@
+use crate::*;
+use fuel_types::Bytes32;
+use zeroize::Zeroize;
+
+#[test]
+fn digest_matches_hasher_output() {
+    let data = b"The lone and level sands stretch far away.";
+    let expected = Hasher::hash(data);
+
+    let digest = Digest::new(data);
+    assert_eq!(digest, expected);
+    assert_eq!(Bytes32::from(digest), Bytes32::from(expected));
+    assert_eq!(digest.into_bytes(), *expected);
+    assert_eq!(digest.as_ref(), expected.as_ref());
+    assert_eq!(Digest::from(Hasher::default().chain(data)), digest);
+    assert_eq!(Digest::from(&Hasher::default().chain(data)), digest);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn digest_displays_as_hex() {
+    let digest = Digest::new(b"");
+    let expected = "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
+
+    assert_eq!(format!("{digest}"), expected);
+    assert_eq!(format!("{digest:#x}"), format!("0x{expected}"));
+}
+
+#[test]
+fn digest_zeroize_clears_the_bytes() {
+    let mut digest = Digest::new(b"secret");
+    digest.zeroize();
+
+    assert_eq!(digest, Digest::default());
+    assert_eq!(digest.into_bytes(), [0u8; Digest::LEN]);
+}
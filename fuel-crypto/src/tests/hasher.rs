@@ -1,4 +1,5 @@
 use crate::*;
+use fuel_types::Bytes32;
 
 #[test]
 fn digest() {
@@ -21,7 +22,7 @@ fn digest() {
 
     let mut h = Hasher::default();
 
-    input.iter().for_each(|i| h.input(i));
+    input.iter().for_each(|i| h.update(i));
 
     let digest = h.finalize();
 
@@ -47,4 +48,349 @@ fn digest() {
     let d = input.iter().collect::<Hasher>().finalize();
 
     assert_eq!(digest, d);
+
+    let d = Hasher::default().chain_all(&input).finalize();
+
+    assert_eq!(digest, d);
+
+    let mut h = Hasher::default();
+
+    h.input_all(&input);
+
+    let d = h.finalize();
+
+    assert_eq!(digest, d);
+}
+
+#[test]
+fn hash_chain_macro_matches_chain() {
+    let expected = Hasher::default()
+        .chain(b"lorem")
+        .chain(b"ipsum")
+        .chain(b"dolor")
+        .finalize();
+
+    let digest = hash_chain!(b"lorem", b"ipsum", b"dolor").finalize();
+
+    assert_eq!(expected, digest);
+    assert_eq!(Hasher::default().finalize(), hash_chain!().finalize());
+}
+
+#[test]
+fn blake3_hash_macro_matches_chain() {
+    let expected = Hasher::default()
+        .chain(b"lorem")
+        .chain(b"ipsum")
+        .chain(b"dolor")
+        .finalize();
+
+    assert_eq!(expected, blake3_hash!(b"lorem", b"ipsum", b"dolor"));
+    assert_eq!(expected, blake3_hash!(b"lorem", b"ipsum", b"dolor",));
+    assert_eq!(
+        hash_chain!(b"lorem", b"ipsum", b"dolor").finalize(),
+        blake3_hash!(b"lorem", b"ipsum", b"dolor")
+    );
+    assert_eq!(Hasher::default().finalize(), blake3_hash!());
+}
+
+#[test]
+fn hash_with_nonce_depends_on_the_nonce() {
+    let data = b"commitment";
+
+    assert_ne!(
+        Hasher::hash_with_nonce(data, 0),
+        Hasher::hash_with_nonce(data, 1)
+    );
+    assert_eq!(
+        Hasher::hash_with_nonce(data, 1),
+        Hasher::hash_with_nonce(data, 1)
+    );
+    assert_eq!(
+        Hasher::hash_with_nonce(data, 1),
+        Hasher::default()
+            .chain(1u64.to_be_bytes())
+            .chain(data)
+            .finalize()
+    );
+}
+
+#[test]
+fn hash_fields_prefixes_each_field_with_its_length() {
+    let expected = Hasher::default()
+        .chain(2u32.to_be_bytes())
+        .chain(b"ab")
+        .chain(1u32.to_be_bytes())
+        .chain(b"c")
+        .finalize();
+
+    assert_eq!(Hasher::hash_fields(&[b"ab", b"c"]), expected);
+}
+
+#[test]
+fn hash_fields_distinguishes_partitionings() {
+    assert_ne!(
+        Hasher::hash_fields(&[b"ab", b"c"]),
+        Hasher::hash_fields(&[b"a", b"bc"])
+    );
+    assert_ne!(
+        Hasher::hash_fields(&[b"abc"]),
+        Hasher::hash_fields(&[b"abc", b""])
+    );
+    assert_ne!(Hasher::hash_fields(&[b"abc"]), Hasher::hash(b"abc"));
+}
+
+#[test]
+fn hash_length_prefixed_distinguishes_partitionings() {
+    assert_ne!(
+        Hasher::hash_length_prefixed(&[b"foo", b"bar"]),
+        Hasher::hash_length_prefixed(&[b"fo", b"obar"])
+    );
+    assert_ne!(
+        Hasher::hash_length_prefixed(&[b"foobar"]),
+        Hasher::hash_length_prefixed(&[b"foobar", b""])
+    );
+
+    let expected = Hasher::default()
+        .chain(3u64.to_be_bytes())
+        .chain(b"foo")
+        .chain(3u64.to_be_bytes())
+        .chain(b"bar")
+        .finalize();
+    assert_eq!(Hasher::hash_length_prefixed(&[b"foo", b"bar"]), expected);
+}
+
+#[test]
+fn hash_length_prefixed_of_no_parts_is_hash_of_empty_input() {
+    assert_eq!(Hasher::hash_length_prefixed(&[]), Hasher::hash(b""));
+    assert_ne!(
+        Hasher::hash_length_prefixed(&[]),
+        Hasher::hash_length_prefixed(&[b""])
+    );
+}
+
+#[test]
+fn verify_mac_accepts_keyed_hash_tag() {
+    let key = [0x42; 32];
+    let data = b"lorem ipsum";
+    let tag = Bytes32::from(Hasher::new_keyed(&key).chain(data).finalize());
+
+    assert_eq!(
+        tag,
+        Bytes32::from(*blake3::keyed_hash(&key, data).as_bytes())
+    );
+    assert!(Hasher::verify_mac(&key, data, &tag));
+}
+
+#[test]
+fn verify_mac_rejects_wrong_tag_key_or_data() {
+    let key = [0x42; 32];
+    let data = b"lorem ipsum";
+    let tag = Bytes32::from(Hasher::new_keyed(&key).chain(data).finalize());
+
+    let mut tampered = tag;
+    tampered[31] ^= 1;
+
+    assert!(!Hasher::verify_mac(&key, data, &tampered));
+    assert!(!Hasher::verify_mac(&[0x43; 32], data, &tag));
+    assert!(!Hasher::verify_mac(&key, b"lorem ipsun", &tag));
+    assert!(!Hasher::verify_mac(&key, data, &Hasher::hash(data).into()));
+}
+
+#[test]
+fn reset_keeps_hasher_key() {
+    let key = [0x42; 32];
+    let mut h = Hasher::new_keyed(&key);
+    h.update(b"dolor");
+    h.reset();
+    h.update(b"lorem");
+
+    assert_eq!(
+        h.finalize(),
+        Hasher::new_keyed(&key).chain(b"lorem").finalize()
+    );
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn heapless_hasher_matches_hasher() {
+    let input: [&[u8]; 3] = [b"lorem", b"ipsum", b"dolor"];
+
+    let mut h = Hasher::default();
+    let mut heapless = HeaplessHasher::default();
+    input.iter().for_each(|i| {
+        h.update(i);
+        heapless.update(i);
+    });
+
+    assert_eq!(h.digest(), heapless.digest());
+    assert_eq!(h.finalize(), heapless.clone().finalize());
+
+    let d = input
+        .iter()
+        .fold(HeaplessHasher::default(), |h, i| h.chain(i))
+        .finalize();
+    assert_eq!(Hasher::default().chain_all(&input).finalize(), d);
+
+    heapless.reset();
+    assert_eq!(Hasher::default().finalize(), heapless.finalize());
+
+    assert_eq!(Hasher::hash(input[0]), HeaplessHasher::hash(input[0]));
+}
+
+#[test]
+fn hash_many_matches_hash() {
+    let inputs: [&[u8]; 3] = [b"", b"a", b"multiple words"];
+
+    let digests: Vec<Digest> = Hasher::hash_many(inputs).collect();
+
+    assert_eq!(digests.len(), inputs.len());
+    for (digest, input) in digests.iter().zip(inputs) {
+        assert_eq!(digest, &Hasher::hash(input));
+    }
+}
+
+#[test]
+fn derive_key_depends_on_context() {
+    let material = b"key material";
+
+    let a = Hasher::new_derive_key("fuel-crypto 2024 test a")
+        .chain(material)
+        .finalize();
+    let b = Hasher::new_derive_key("fuel-crypto 2024 test b")
+        .chain(material)
+        .finalize();
+
+    assert_ne!(a, b);
+    assert_ne!(a, Hasher::hash(material));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn commit_vector_binds_the_list_length() {
+    let lists: [&[&[u8]]; 5] = [&[], &[b""], &[b"", b""], &[b"ab"], &[b"a", b"b"]];
+
+    for (i, a) in lists.iter().enumerate() {
+        for (j, b) in lists.iter().enumerate() {
+            if i != j {
+                assert_ne!(Hasher::commit_vector(*a), Hasher::commit_vector(*b));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn commit_vector_hashes_count_followed_by_elements() {
+    let elements: [&[u8]; 3] = [b"a", b"bc", b""];
+
+    let expected = Hasher::default()
+        .chain(3u64.to_be_bytes())
+        .chain(b"abc")
+        .finalize();
+
+    assert_eq!(Hasher::commit_vector(elements), expected);
+    assert_eq!(
+        Hasher::commit_vector(elements),
+        Hasher::compute_commitment(elements)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn compute_commitment_hashes_count_followed_by_elements() {
+    let elements: [&[u8]; 3] = [b"a", b"bc", b""];
+
+    let expected = Hasher::default()
+        .chain(3u64.to_be_bytes())
+        .chain(b"abc")
+        .finalize();
+
+    assert_eq!(Hasher::compute_commitment(elements), expected);
+    assert_eq!(
+        Hasher::compute_commitment(elements.iter().filter(|element| !element.is_empty())),
+        Hasher::default()
+            .chain(2u64.to_be_bytes())
+            .chain(b"abc")
+            .finalize()
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn compute_commitment_changes_when_elements_are_added_removed_or_reordered() {
+    let lists: [&[&[u8]]; 6] = [
+        &[],
+        &[b"a"],
+        &[b"a", b"b"],
+        &[b"b", b"a"],
+        &[b"a", b"b", b"c"],
+        &[b"a", b"c"],
+    ];
+
+    for (i, a) in lists.iter().enumerate() {
+        for (j, b) in lists.iter().enumerate() {
+            if i != j {
+                assert_ne!(
+                    Hasher::compute_commitment(*a),
+                    Hasher::compute_commitment(*b)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn absorb_integers_matches_big_endian_bytes() {
+    let mut absorbed = Hasher::default();
+    absorbed.absorb_u64(0x0102_0304_0506_0708);
+    absorbed.absorb_u32(0x0a0b_0c0d);
+    absorbed.absorb_u16(0xbeef);
+    absorbed.absorb_u8(0x7f);
+
+    let mut manual = Hasher::default();
+    manual.update(0x0102_0304_0506_0708u64.to_be_bytes());
+    manual.update(0x0a0b_0c0du32.to_be_bytes());
+    manual.update(0xbeefu16.to_be_bytes());
+    manual.update([0x7fu8]);
+
+    let chained = Hasher::default()
+        .chain_u64(0x0102_0304_0506_0708)
+        .chain_u32(0x0a0b_0c0d)
+        .chain_u16(0xbeef)
+        .chain_u8(0x7f);
+
+    let expected = manual.finalize();
+    assert_eq!(absorbed.finalize(), expected);
+    assert_eq!(chained.finalize(), expected);
+}
+
+#[test]
+fn finalize_xof_starts_with_the_digest() {
+    let hasher = Hasher::default().chain(b"extendable output");
+
+    let mut output = [0u8; 64];
+    hasher.clone().finalize_xof().fill(&mut output);
+
+    assert_eq!(output[..32], *hasher.finalize());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn output_reader_async_read_matches_read() {
+    use std::io::Read;
+    use tokio::io::AsyncReadExt;
+
+    let hasher = Hasher::default().chain(b"async key derivation");
+
+    let mut expected = [0u8; 128];
+    Read::read_exact(&mut hasher.clone().finalize_xof(), &mut expected).unwrap();
+
+    let mut output = [0u8; 128];
+    let mut reader = hasher.finalize_xof();
+    AsyncReadExt::read_exact(&mut reader, &mut output)
+        .await
+        .unwrap();
+
+    assert_eq!(output, expected);
+    assert_eq!(reader.position(), 128);
 }
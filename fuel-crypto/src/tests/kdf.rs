@@ -0,0 +1,35 @@
+use crate::kdf::{
+    derive_child_key,
+    derive_key_path,
+};
+
+const ROOT_KEY: [u8; 32] = [0xab; 32];
+
+#[test]
+fn derive_child_key_is_blake3_keyed_hash_of_index() {
+    let expected = *blake3::keyed_hash(&ROOT_KEY, &7u32.to_be_bytes()).as_bytes();
+
+    assert_eq!(derive_child_key(&ROOT_KEY, 7), expected);
+}
+
+#[test]
+fn derive_child_key_differs_per_index() {
+    let child_0 = derive_child_key(&ROOT_KEY, 0);
+    let child_1 = derive_child_key(&ROOT_KEY, 1);
+
+    assert_ne!(child_0, child_1);
+    assert_ne!(child_0, ROOT_KEY);
+}
+
+#[test]
+fn derive_key_path_chains_child_derivations() {
+    let expected =
+        derive_child_key(&derive_child_key(&derive_child_key(&ROOT_KEY, 44), 1), 0);
+
+    assert_eq!(derive_key_path(&ROOT_KEY, &[44, 1, 0]), expected);
+}
+
+#[test]
+fn derive_key_path_returns_root_key_for_empty_path() {
+    assert_eq!(derive_key_path(&ROOT_KEY, &[]), ROOT_KEY);
+}
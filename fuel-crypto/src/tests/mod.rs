@@ -1,8 +1,13 @@
 // It is used in the benches
 use criterion as _;
 use k256 as _;
+#[cfg(not(feature = "tokio"))]
+use tokio as _;
 
+mod cpu_features;
+mod digest;
 mod hasher;
+mod kdf;
 
 #[cfg(feature = "std")]
 mod mnemonic;
@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+
+# Runs the `fuel-merkle` benchmarks against a saved criterion baseline and fails
+# if the mean time of any benchmark regressed by more than 10%.
+#
+# Save the baseline first, e.g. on the target branch:
+#   cargo bench -p fuel-merkle --features bench --bench merkle -- --save-baseline main
+# Then compare the current tree against it:
+#   ./fuel-merkle/benches/check_regression.sh main
+#
+# Requires `jq`. The threshold can be changed with `THRESHOLD` (a fraction).
+
+set -euo pipefail
+
+BASELINE="${1:-main}"
+THRESHOLD="${THRESHOLD:-0.10}"
+
+cd "$(dirname "$0")/../.."
+
+cargo bench -p fuel-merkle --features bench --bench merkle -- --baseline "$BASELINE"
+
+status=0
+while IFS= read -r -d '' estimates; do
+    change=$(jq '.mean.point_estimate' "$estimates")
+    if awk -v change="$change" -v threshold="$THRESHOLD" 'BEGIN { exit !(change > threshold) }'; then
+        benchmark=$(dirname "$(dirname "$estimates")")
+        echo "Regression in ${benchmark#target/criterion/}: mean changed by ${change}"
+        status=1
+    fi
+done < <(find target/criterion -path 'target/criterion/merkle-*/change/estimates.json' -print0)
+
+exit $status
@@ -0,0 +1,173 @@
+use criterion::{
+    BatchSize,
+    BenchmarkId,
+    Criterion,
+    black_box,
+    criterion_group,
+    criterion_main,
+};
+use fuel_merkle::{
+    binary,
+    common::Bytes32,
+    sparse::{
+        MerkleTreeKey,
+        NodeCache,
+        in_memory,
+        proof::Proof,
+    },
+};
+use rand::{
+    Rng,
+    SeedableRng,
+    rngs::StdRng,
+};
+
+fn random_bytes32<R>(rng: &mut R) -> Bytes32
+where
+    R: Rng + ?Sized,
+{
+    let mut bytes = [0u8; 32];
+    rng.fill(bytes.as_mut());
+    bytes
+}
+
+fn random_set<R>(rng: &mut R, size: usize) -> Vec<(MerkleTreeKey, Bytes32)>
+where
+    R: Rng + ?Sized,
+{
+    (0..size)
+        .map(|_| (MerkleTreeKey::new(random_bytes32(rng)), random_bytes32(rng)))
+        .collect()
+}
+
+fn sparse_from_set(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(8586);
+    let mut group = c.benchmark_group("merkle-sparse-from-set");
+    group.sample_size(10);
+
+    for size in [1_000, 10_000, 100_000] {
+        let set = random_set(rng, size);
+        group.bench_with_input(BenchmarkId::new("from-set", size), &set, |b, set| {
+            b.iter(|| in_memory::MerkleTree::from_set(black_box(set.clone().into_iter())))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("root-from-set", size),
+            &set,
+            |b, set| {
+                b.iter(|| {
+                    in_memory::MerkleTree::root_from_set(black_box(
+                        set.clone().into_iter(),
+                    ))
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn sparse_proofs(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(8586);
+    let set = random_set(rng, 100_000);
+    let tree = in_memory::MerkleTree::from_set(set.clone().into_iter());
+    let root = tree.root();
+    let (key, data) = set[set.len() / 2];
+
+    let mut group = c.benchmark_group("merkle-sparse-proof");
+
+    group.bench_function("generate-proof", |b| {
+        b.iter(|| tree.generate_proof(black_box(&key)))
+    });
+
+    let mut cache = NodeCache::new(1024);
+    let _ = tree.generate_proof_with_cache(&key, &mut cache);
+    group.bench_function("generate-proof-warm-cache", |b| {
+        b.iter(|| tree.generate_proof_with_cache(black_box(&key), &mut cache))
+    });
+
+    let Ok(Proof::Inclusion(proof)) = tree.generate_proof(&key) else {
+        panic!("Expected an inclusion proof")
+    };
+    assert!(proof.verify(&root, &key, &data));
+    group.bench_function("verify-proof", |b| {
+        b.iter(|| proof.verify(black_box(&root), black_box(&key), black_box(&data)))
+    });
+
+    group.finish();
+}
+
+fn sparse_update_batch(c: &mut Criterion) {
+    let rng = &mut StdRng::seed_from_u64(8586);
+    let set = random_set(rng, 10_000);
+    let batch = random_set(rng, 1_000);
+
+    let mut group = c.benchmark_group("merkle-sparse-update-batch");
+    group.sample_size(10);
+
+    group.bench_function("update-1000", |b| {
+        b.iter_batched(
+            || in_memory::MerkleTree::from_set(set.clone().into_iter()),
+            |mut tree| {
+                for (key, data) in batch.iter() {
+                    tree.update(*key, data);
+                }
+                tree.root()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+fn binary_proofs(c: &mut Criterion) {
+    const LEAVES_COUNT: u64 = 100_000;
+
+    let rng = &mut StdRng::seed_from_u64(8586);
+    let leaves = (0..LEAVES_COUNT)
+        .map(|_| random_bytes32(rng))
+        .collect::<Vec<_>>();
+    let mut tree = binary::in_memory::MerkleTree::new();
+    for leaf in leaves.iter() {
+        tree.push(leaf);
+    }
+    let index = LEAVES_COUNT / 2;
+    let data = leaves[leaves.len() / 2];
+
+    let mut group = c.benchmark_group("merkle-binary-proof");
+
+    group.bench_function("generate-proof", |b| {
+        b.iter(|| tree.prove(black_box(index)))
+    });
+
+    let (root, proof_set) = tree.prove(index).expect("Expected a proof");
+    assert!(binary::verify(
+        &root,
+        &data,
+        &proof_set,
+        index,
+        LEAVES_COUNT
+    ));
+    group.bench_function("verify-proof", |b| {
+        b.iter(|| {
+            binary::verify(
+                black_box(&root),
+                black_box(&data),
+                black_box(&proof_set),
+                index,
+                LEAVES_COUNT,
+            )
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    sparse_from_set,
+    sparse_proofs,
+    sparse_update_batch,
+    binary_proofs
+);
+criterion_main!(benches);
@@ -0,0 +1,7 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proof.proto");
+    // `protox` parses the schema, so building doesn't require `protoc`
+    let file_descriptors = protox::compile(["proof.proto"], ["."])?;
+    prost_build::Config::new().compile_fds(file_descriptors)?;
+    Ok(())
+}
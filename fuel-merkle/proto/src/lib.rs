@@ -0,0 +1,9 @@
+//! The protobuf message types of the sparse Merkle tree proofs.
+//!
+//! The types are generated by `prost` from the `proof.proto` schema. The
+//! conversions from and to the native types of `fuel-merkle` are provided by
+//! `fuel-merkle` itself, behind its `proto` feature.
+
+#![no_std]
+
+include!(concat!(env!("OUT_DIR"), "/fuel_merkle.sparse.rs"));
@@ -1,22 +1,43 @@
 mod hash;
 mod merkle_tree;
+mod navigator;
 mod node;
 mod primitive;
 mod verify;
 
-pub(crate) use hash::{
-    empty_sum,
-    node_sum,
-};
+use crate::common::Bytes32;
+
+pub(crate) use hash::empty_sum;
 pub(crate) use node::Node;
 
-pub use hash::leaf_sum;
+pub use hash::{
+    keyed_leaf_sum,
+    keyed_node_sum,
+    leaf_sum,
+    node_sum,
+};
 pub use merkle_tree::{
     MerkleTree,
     MerkleTreeError,
 };
+pub use navigator::TreeNavigator;
 pub use primitive::Primitive;
 pub mod in_memory;
 pub mod root_calculator;
 
-pub use verify::verify;
+pub use verify::{
+    BinaryProof,
+    InvalidProofLength,
+    SubrootProof,
+    verify,
+    verify_subroot_proof,
+};
+
+/// An in-memory binary Merkle tree whose leaves can only be appended.
+///
+/// The type has no way to modify or remove a leaf once it is pushed, which
+/// suits append-only logs such as a list of transactions.
+pub type AppendOnlyBinaryTree = in_memory::MerkleTree<in_memory::AppendOnly>;
+
+/// The root of an empty binary Merkle tree.
+pub const EMPTY_BINARY_ROOT: Bytes32 = *empty_sum();
@@ -2,6 +2,8 @@ use crate::common::{
     Bytes32,
     Prefix,
     empty_sum_blake3,
+    sum_node_with_prefix,
+    sum_with_prefix,
 };
 
 // Merkle Tree hash of an empty list
@@ -12,23 +14,70 @@ pub const fn empty_sum() -> &'static Bytes32 {
 
 // Merkle tree hash of an n-element list D[n]
 // MTH(D[n]) = Hash(0x01 || MTH(D[0:k]) || MTH(D[k:n])
+#[must_use = "calling node_sum() without using the result is a likely bug"]
 pub fn node_sum(lhs_data: &Bytes32, rhs_data: &Bytes32) -> Bytes32 {
-    let mut hasher = blake3::Hasher::new();
+    sum_node_with_prefix(Prefix::Node, lhs_data, rhs_data)
+}
+
+// Merkle tree hash of a list with one entry
+// MTH({d(0)}) = Hash(0x00 || d(0))
+#[must_use = "calling leaf_sum() without using the result is a likely bug"]
+pub fn leaf_sum(data: &[u8]) -> Bytes32 {
+    sum_with_prefix(Prefix::Leaf, data)
+}
+
+// Keyed Merkle tree hash of an n-element list D[n]
+// MTH_K(D[n]) = Hash_K(0x01 || MTH_K(D[0:k]) || MTH_K(D[k:n]))
+#[must_use = "calling keyed_node_sum() without using the result is a likely bug"]
+pub fn keyed_node_sum(key: &[u8; 32], lhs_data: &Bytes32, rhs_data: &Bytes32) -> Bytes32 {
+    let mut hasher = blake3::Hasher::new_keyed(key);
 
     hasher.update(Prefix::Node.as_ref());
     hasher.update(lhs_data);
     hasher.update(rhs_data);
 
-    (*hasher.finalize().as_bytes()).into()
+    *hasher.finalize().as_bytes()
 }
 
-// Merkle tree hash of a list with one entry
-// MTH({d(0)}) = Hash(0x00 || d(0))
-pub fn leaf_sum(data: &[u8]) -> Bytes32 {
-    let mut hasher = blake3::Hasher::new();
+// Keyed Merkle tree hash of a list with one entry
+// MTH_K({d(0)}) = Hash_K(0x00 || d(0))
+#[must_use = "calling keyed_leaf_sum() without using the result is a likely bug"]
+pub fn keyed_leaf_sum(key: &[u8; 32], data: &[u8]) -> Bytes32 {
+    let mut hasher = blake3::Hasher::new_keyed(key);
 
     hasher.update(Prefix::Leaf.as_ref());
     hasher.update(data);
 
-    (*hasher.finalize().as_bytes()).into()
+    *hasher.finalize().as_bytes()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keyed_sums_are_separated_by_key() {
+        let (key_a, key_b) = ([0xaa; 32], [0xbb; 32]);
+        let (lhs, rhs) = (leaf_sum(b"lhs"), leaf_sum(b"rhs"));
+
+        assert_ne!(keyed_leaf_sum(&key_a, b"data"), keyed_leaf_sum(&key_b, b"data"));
+        assert_ne!(keyed_leaf_sum(&key_a, b"data"), leaf_sum(b"data"));
+        assert_ne!(
+            keyed_node_sum(&key_a, &lhs, &rhs),
+            keyed_node_sum(&key_b, &lhs, &rhs)
+        );
+        assert_ne!(keyed_node_sum(&key_a, &lhs, &rhs), node_sum(&lhs, &rhs));
+    }
+
+    #[test]
+    fn keyed_sums_keep_leaf_and_node_domains_apart() {
+        let key = [0xaa; 32];
+        let (lhs, rhs) = (leaf_sum(b"lhs"), leaf_sum(b"rhs"));
+        let concatenated = [lhs, rhs].concat();
+
+        assert_ne!(
+            keyed_leaf_sum(&key, &concatenated),
+            keyed_node_sum(&key, &lhs, &rhs)
+        );
+    }
 }
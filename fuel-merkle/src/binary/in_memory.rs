@@ -1,7 +1,9 @@
 use crate::{
+    Error,
     binary::{
         self,
         Primitive,
+        root_calculator::MerkleRootCalculator,
     },
     common::{
         Bytes32,
@@ -11,6 +13,23 @@ use crate::{
     storage::Mappable,
 };
 
+use alloc::{
+    collections::BTreeMap,
+    vec::Vec,
+};
+use core::{
+    convert::Infallible,
+    marker::PhantomData,
+};
+#[cfg(feature = "std")]
+use std::sync::{
+    Arc,
+    PoisonError,
+    RwLock,
+    RwLockReadGuard,
+    RwLockWriteGuard,
+};
+
 /// The table of the Binary Merkle Tree's nodes. [`MerkleTree`] works with it as
 /// a binary array, where the storage key of the node is the `u64` index and
 /// value is the [`Node`](crate::binary::Node).
@@ -27,28 +46,143 @@ impl Mappable for NodesTable {
 type Storage = StorageMap<NodesTable>;
 type BinaryMerkleTree = binary::MerkleTree<NodesTable, Storage>;
 
+#[derive(Debug, Clone, derive_more::Display, PartialEq, Eq)]
+pub enum UpdateError {
+    #[display(fmt = "leaf index {_0} is out of bounds")]
+    IndexOutOfBounds(u64),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UpdateError {}
+
+#[derive(Debug, Clone, derive_more::Display, PartialEq, Eq)]
+pub enum FromSetError {
+    #[display(fmt = "leaf index {_0} is missing from the set")]
+    MissingIndex(u64),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromSetError {}
+
+/// Marker for a [`MerkleTree`] whose leaves can only be appended. Such a tree
+/// is also available as [`AppendOnlyBinaryTree`](binary::AppendOnlyBinaryTree).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AppendOnly;
+
+/// Marker for a [`MerkleTree`] whose leaves can be appended, updated and
+/// deleted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mutable;
+
+/// An in-memory binary Merkle tree.
+///
+/// The `Mode` decides at compile time which operations the tree supports: a
+/// [`Mutable`] tree can overwrite and delete its leaves, while an
+/// [`AppendOnly`] tree, created with `default` or obtained with
+/// [`into_append_only`](MerkleTree::into_append_only), can only grow.
 #[derive(Debug, Clone)]
-pub struct MerkleTree {
+pub struct MerkleTree<Mode = Mutable> {
     tree: BinaryMerkleTree,
+    mode: PhantomData<Mode>,
 }
 
-impl MerkleTree {
+impl MerkleTree<Mutable> {
     pub fn new() -> Self {
         Self {
             tree: BinaryMerkleTree::new(Storage::new()),
+            mode: PhantomData,
+        }
+    }
+
+    /// Builds a tree whose leaves have the given pre-computed leaf hashes,
+    /// i.e. each entry is used as the [`leaf_sum`](binary::leaf_sum) of a
+    /// leaf without hashing it again.
+    pub fn from_leaf_hashes(hashes: &[Bytes32]) -> Self {
+        let mut tree = Self::new();
+        for hash in hashes {
+            let _ = tree.tree.push_leaf_hash(*hash);
+        }
+        tree
+    }
+
+    /// Build a binary Merkle tree from a set of indexed leaves. This is
+    /// equivalent to creating an empty tree and calling [push](Self::push)
+    /// with the data of each leaf in ascending order of the indices. A later
+    /// leaf replaces an earlier one with the same index.
+    ///
+    /// Fails with [`FromSetError::MissingIndex`] if the indices of the leaves
+    /// are not exactly `0..n` for some `n`.
+    #[must_use = "from_set() returns the constructed tree"]
+    pub fn from_set<I, D>(leaves: I) -> Result<Self, FromSetError>
+    where
+        I: Iterator<Item = (u64, D)>,
+        D: AsRef<[u8]>,
+    {
+        let mut tree = Self::new();
+        for data in ordered_leaves(leaves)? {
+            tree.push(data.as_ref());
         }
+        Ok(tree)
+    }
+
+    /// Calculate the binary Merkle root from a set of indexed leaves. This is
+    /// equivalent to calling [from_set](Self::from_set), followed by
+    /// `root()`, but the nodes are not written to storage.
+    ///
+    /// Fails with [`FromSetError::MissingIndex`] if the indices of the leaves
+    /// are not exactly `0..n` for some `n`.
+    #[must_use = "calling root_from_set() without using the result is a likely bug"]
+    pub fn root_from_set<I, D>(leaves: I) -> Result<Bytes32, FromSetError>
+    where
+        I: Iterator<Item = (u64, D)>,
+        D: AsRef<[u8]>,
+    {
+        let leaves = ordered_leaves(leaves)?;
+        Ok(MerkleRootCalculator::new().root_from_iterator(leaves))
     }
 
     pub fn push(&mut self, data: &[u8]) {
         let _ = self.tree.push(data);
     }
 
-    pub fn root(&self) -> Bytes32 {
-        self.tree.root()
+    /// Turns the tree into an [`AppendOnly`] tree with the same leaves.
+    pub fn into_append_only(self) -> MerkleTree<AppendOnly> {
+        MerkleTree {
+            tree: self.tree,
+            mode: PhantomData,
+        }
+    }
+
+    /// Replaces the leaf at `index` with a leaf of `data`, recomputing only
+    /// the nodes on the path from the leaf to the root.
+    pub fn update(&mut self, index: u64, data: &[u8]) -> Result<(), UpdateError> {
+        match self.tree.update(index, data) {
+            Ok(()) => Ok(()),
+            Err(binary::MerkleTreeError::IndexOutOfBounds(index)) => {
+                Err(UpdateError::IndexOutOfBounds(index))
+            }
+            Err(err) => {
+                unreachable!("the in-memory storage holds every node of the tree: {err}")
+            }
+        }
     }
 
-    pub fn prove(&self, proof_index: u64) -> Option<(Bytes32, ProofSet)> {
-        self.tree.prove(proof_index).ok()
+    /// Removes the leaf at `index`, moving every following leaf one index
+    /// down.
+    ///
+    /// The tree is rebuilt from the remaining leaves, so a deletion costs
+    /// `O(n)` hashes and allocations for a tree of `n` leaves, against
+    /// `O(log n)` for [`update`](Self::update).
+    pub fn delete(&mut self, index: u64) -> Result<(), UpdateError> {
+        let mut leaves = self.leaf_hashes();
+        let position = usize::try_from(index)
+            .ok()
+            .filter(|position| *position < leaves.len())
+            .ok_or(UpdateError::IndexOutOfBounds(index))?;
+        leaves.remove(position);
+
+        *self = Self::from_leaf_hashes(&leaves);
+        Ok(())
     }
 
     pub fn reset(&mut self) {
@@ -56,20 +190,171 @@ impl MerkleTree {
     }
 }
 
-impl Default for MerkleTree {
+/// The data of the `leaves` in ascending order of their indices, which must
+/// be exactly `0..n` once the duplicates are removed.
+fn ordered_leaves<I, D>(leaves: I) -> Result<impl Iterator<Item = D>, FromSetError>
+where
+    I: Iterator<Item = (u64, D)>,
+{
+    let sorted = leaves.collect::<BTreeMap<u64, D>>();
+    if let Some(missing) = (0u64..)
+        .zip(sorted.keys())
+        .find_map(|(expected, index)| (*index != expected).then_some(expected))
+    {
+        return Err(FromSetError::MissingIndex(missing))
+    }
+    Ok(sorted.into_values())
+}
+
+impl MerkleTree<AppendOnly> {
+    /// Append a leaf holding `data`, returning its index and the new root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree already holds the maximum number of leaves.
+    pub fn push(&mut self, data: &[u8]) -> (u64, Bytes32) {
+        let index = self.tree.leaves_count();
+        self.tree
+            .push(data)
+            .expect("`Storage` can't return error and the tree can't be full");
+        (index, self.tree.root())
+    }
+
+    /// Returns the proof set of the leaf at `index`, ordered from the leaf to
+    /// the root.
+    #[must_use = "calling generate_proof() without using the result is a likely bug"]
+    pub fn generate_proof(&self, index: u64) -> Result<ProofSet, Error<Infallible>> {
+        let (_, proof_set) = self.tree.prove(index)?;
+        Ok(proof_set)
+    }
+}
+
+impl<Mode> MerkleTree<Mode> {
+    #[must_use = "calling root() without using the result is a likely bug"]
+    pub fn root(&self) -> Bytes32 {
+        self.tree.root()
+    }
+
+    #[must_use = "calling prove() without using the result is a likely bug"]
+    pub fn prove(
+        &self,
+        proof_index: u64,
+    ) -> Result<(Bytes32, ProofSet), Error<Infallible>> {
+        Ok(self.tree.prove(proof_index)?)
+    }
+
+    /// The hash of the leaf at `index`, or `None` if the tree has no such
+    /// leaf.
+    #[must_use = "calling leaf_hash_at() without using the result is a likely bug"]
+    pub fn leaf_hash_at(&self, index: u64) -> Option<Bytes32> {
+        self.tree
+            .leaf_hash_at(index)
+            .expect("the in-memory storage holds every leaf of the tree")
+    }
+
+    /// The hashes of all the leaves of the tree, in insertion order.
+    #[must_use = "calling leaf_hashes() without using the result is a likely bug"]
+    pub fn leaf_hashes(&self) -> Vec<Bytes32> {
+        self.tree
+            .leaf_hashes()
+            .expect("the in-memory storage holds every leaf of the tree")
+    }
+
+    pub fn total_leaves(&self) -> u64 {
+        self.tree.leaves_count()
+    }
+}
+
+impl Default for MerkleTree<Mutable> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl Default for MerkleTree<AppendOnly> {
+    fn default() -> Self {
+        MerkleTree::new().into_append_only()
+    }
+}
+
+/// A [`MerkleTree`] that can be shared between threads.
+///
+/// Clones share the same tree. Reads such as [`root`](Self::root) and
+/// [`prove`](Self::prove) take a read lock and run concurrently, while writes
+/// take the write lock. The lock is recovered from poisoning, like in
+/// [`sparse::concurrent`](crate::sparse::concurrent): it is only held by the
+/// methods of the tree, which don't run the caller's code.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct SyncBinaryMerkleTree {
+    tree: Arc<RwLock<MerkleTree>>,
+}
+
+#[cfg(feature = "std")]
+impl SyncBinaryMerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, MerkleTree> {
+        self.tree.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, MerkleTree> {
+        self.tree.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn push(&self, data: &[u8]) {
+        self.write().push(data)
+    }
+
+    #[must_use = "calling root() without using the result is a likely bug"]
+    pub fn root(&self) -> Bytes32 {
+        self.read().root()
+    }
+
+    #[must_use = "calling prove() without using the result is a likely bug"]
+    pub fn prove(
+        &self,
+        proof_index: u64,
+    ) -> Result<(Bytes32, ProofSet), Error<Infallible>> {
+        self.read().prove(proof_index)
+    }
+
+    /// The hash of the leaf at `index`, or `None` if the tree has no such
+    /// leaf.
+    #[must_use = "calling leaf_hash_at() without using the result is a likely bug"]
+    pub fn leaf_hash_at(&self, index: u64) -> Option<Bytes32> {
+        self.read().leaf_hash_at(index)
+    }
+
+    /// The hashes of all the leaves of the tree, in insertion order.
+    #[must_use = "calling leaf_hashes() without using the result is a likely bug"]
+    pub fn leaf_hashes(&self) -> Vec<Bytes32> {
+        self.read().leaf_hashes()
+    }
+
+    /// Replaces the leaf at `index` with a leaf of `data`, recomputing only
+    /// the nodes on the path from the leaf to the root.
+    pub fn update(&self, index: u64, data: &[u8]) -> Result<(), UpdateError> {
+        self.write().update(index, data)
+    }
+
+    pub fn reset(&self) {
+        self.write().reset()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use binary::{
+        AppendOnlyBinaryTree,
         empty_sum,
         leaf_sum,
         node_sum,
     };
+    use crate::VerifyError;
     use fuel_merkle_test_helpers::TEST_DATA;
 
     #[test]
@@ -80,6 +365,23 @@ mod test {
         assert_eq!(root, empty_sum().clone());
     }
 
+    #[test]
+    fn from_leaf_hashes_returns_the_same_root_as_pushing_the_leaves() {
+        let data = &TEST_DATA[0..7];
+        let hashes = data.iter().map(|datum| leaf_sum(datum)).collect::<Vec<_>>();
+
+        let mut expected = MerkleTree::new();
+        expected.push(data[0]);
+        assert_eq!(MerkleTree::from_leaf_hashes(&hashes[..1]).root(), expected.root());
+
+        for datum in &data[1..] {
+            expected.push(datum);
+        }
+        let tree = MerkleTree::from_leaf_hashes(&hashes);
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.leaf_hashes(), hashes);
+    }
+
     #[test]
     fn root_returns_the_merkle_root_for_1_leaf() {
         let mut tree = MerkleTree::new();
@@ -139,15 +441,18 @@ mod test {
     }
 
     #[test]
-    fn prove_returns_none_for_0_leaves() {
+    fn prove_returns_invalid_proof_index_for_0_leaves() {
         let tree = MerkleTree::new();
 
         let proof = tree.prove(0);
-        assert!(proof.is_none());
+        assert!(matches!(
+            proof,
+            Err(Error::InvalidProof(VerifyError::InvalidProofIndex(0)))
+        ));
     }
 
     #[test]
-    fn prove_returns_none_when_index_is_greater_than_number_of_leaves() {
+    fn prove_returns_invalid_proof_index_when_index_is_greater_than_number_of_leaves() {
         let mut tree = MerkleTree::new();
 
         let data = &TEST_DATA[0..5]; // 5 leaves
@@ -156,7 +461,10 @@ mod test {
         }
 
         let proof = tree.prove(10);
-        assert!(proof.is_none());
+        assert!(matches!(
+            proof,
+            Err(Error::InvalidProof(VerifyError::InvalidProofIndex(10)))
+        ));
     }
 
     #[test]
@@ -265,4 +573,252 @@ mod test {
             assert_eq!(proof_set[1], node_3);
         }
     }
+
+    #[test]
+    fn update_matches_a_tree_built_with_the_updated_data() {
+        let mut data = TEST_DATA[0..7].to_vec(); // 7 leaves
+        let mut tree = MerkleTree::new();
+        for datum in data.iter() {
+            tree.push(datum);
+        }
+
+        for index in 0..7 {
+            data[index] = b"Updated";
+            tree.update(index as u64, b"Updated").unwrap();
+
+            let mut expected = MerkleTree::new();
+            for datum in data.iter() {
+                expected.push(datum);
+            }
+            assert_eq!(tree.root(), expected.root());
+            for proof_index in 0..7 {
+                assert_eq!(
+                    tree.prove(proof_index).unwrap(),
+                    expected.prove(proof_index).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn update_returns_index_out_of_bounds_error_for_missing_leaf() {
+        let mut tree = MerkleTree::new();
+        assert_eq!(
+            tree.update(0, b"DATA"),
+            Err(UpdateError::IndexOutOfBounds(0))
+        );
+
+        tree.push(b"DATA");
+        let root = tree.root();
+        assert_eq!(
+            tree.update(1, b"DATA"),
+            Err(UpdateError::IndexOutOfBounds(1))
+        );
+        assert_eq!(tree.root(), root);
+    }
+
+    #[test]
+    fn leaf_hashes_returns_the_leaf_sum_of_every_leaf_in_order() {
+        let mut tree = MerkleTree::new();
+        assert_eq!(tree.leaf_hashes(), Vec::<Bytes32>::new());
+        assert_eq!(tree.leaf_hash_at(0), None);
+
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum);
+        }
+
+        let expected = data.iter().map(|datum| leaf_sum(datum)).collect::<Vec<_>>();
+        assert_eq!(tree.leaf_hashes(), expected);
+        for (index, datum) in (0u64..).zip(data.iter()) {
+            assert_eq!(tree.leaf_hash_at(index), Some(leaf_sum(datum)));
+        }
+        assert_eq!(tree.leaf_hash_at(7), None);
+    }
+
+    #[test]
+    fn delete_removes_the_leaf_and_shifts_the_following_leaves() {
+        let mut tree = MerkleTree::new();
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum);
+        }
+
+        tree.delete(2).unwrap();
+
+        let mut expected = MerkleTree::new();
+        for datum in data[0..2].iter().chain(&data[3..]) {
+            expected.push(datum);
+        }
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.leaf_hashes(), expected.leaf_hashes());
+        assert_eq!(tree.prove(5).unwrap(), expected.prove(5).unwrap());
+        assert_eq!(tree.delete(6), Err(UpdateError::IndexOutOfBounds(6)));
+    }
+
+    #[test]
+    fn into_append_only_keeps_the_leaves_of_the_tree() {
+        let mut tree = MerkleTree::new();
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data[0..4].iter() {
+            tree.push(datum);
+        }
+
+        let mut append_only = tree.clone().into_append_only();
+        assert_eq!(append_only.root(), tree.root());
+
+        for datum in data[4..].iter() {
+            tree.push(datum);
+            append_only.push(datum);
+        }
+        assert_eq!(append_only.root(), tree.root());
+        assert_eq!(append_only.prove(6).unwrap(), tree.prove(6).unwrap());
+    }
+
+    #[test]
+    fn append_only_push_returns_the_leaf_index_and_the_new_root() {
+        let mut tree = AppendOnlyBinaryTree::default();
+        let mut reference = MerkleTree::new();
+
+        for (expected_index, datum) in (0u64..).zip(TEST_DATA.iter()) {
+            reference.push(datum);
+            let (index, root) = tree.push(datum);
+
+            assert_eq!(index, expected_index);
+            assert_eq!(root, reference.root());
+            assert_eq!(tree.root(), root);
+        }
+        assert_eq!(tree.total_leaves(), TEST_DATA.len() as u64);
+    }
+
+    #[test]
+    fn append_only_generate_proof_returns_a_valid_proof_for_every_leaf() {
+        let mut tree = AppendOnlyBinaryTree::default();
+        for datum in TEST_DATA.iter() {
+            let _ = tree.push(datum);
+        }
+        let root = tree.root();
+
+        for (index, datum) in (0u64..).zip(TEST_DATA.iter()) {
+            let proof_set = tree.generate_proof(index).unwrap();
+            assert!(binary::verify(
+                &root,
+                datum,
+                &proof_set,
+                index,
+                tree.total_leaves(),
+            ));
+        }
+
+        assert!(matches!(
+            tree.generate_proof(tree.total_leaves()),
+            Err(Error::InvalidProof(VerifyError::InvalidProofIndex(_)))
+        ));
+    }
+
+    #[test]
+    fn from_set_orders_the_leaves_by_index() {
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        let mut expected = MerkleTree::new();
+        for datum in data.iter() {
+            expected.push(datum);
+        }
+
+        let leaves = (0u64..).zip(data.iter()).collect::<Vec<_>>();
+        let tree = MerkleTree::from_set(leaves.iter().rev().copied()).unwrap();
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.leaf_hashes(), expected.leaf_hashes());
+        let root = MerkleTree::root_from_set(leaves.iter().rev().copied()).unwrap();
+        assert_eq!(root, expected.root());
+        assert_eq!(
+            MerkleTree::root_from_set(core::iter::empty::<(u64, &[u8])>()),
+            Ok(*empty_sum())
+        );
+    }
+
+    #[test]
+    fn from_set_returns_missing_index_error_for_missing_indices() {
+        let leaves = [(0u64, b"a"), (2, b"c")];
+        assert_eq!(
+            MerkleTree::from_set(leaves.into_iter()).unwrap_err(),
+            FromSetError::MissingIndex(1)
+        );
+        assert_eq!(
+            MerkleTree::root_from_set(leaves.into_iter()),
+            Err(FromSetError::MissingIndex(1))
+        );
+        assert_eq!(
+            MerkleTree::root_from_set([(1u64, b"b")].into_iter()),
+            Err(FromSetError::MissingIndex(0))
+        );
+    }
+
+    #[test]
+    fn mutating_a_clone_does_not_change_the_original_tree() {
+        let mut tree = MerkleTree::new();
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data[0..4].iter() {
+            tree.push(datum);
+        }
+        let root = tree.root();
+
+        let mut clone = tree.clone();
+        clone.push(data[4]);
+        clone.update(0, data[5]).unwrap();
+        clone.delete(1).unwrap();
+        assert_ne!(clone.root(), root);
+
+        assert_eq!(tree.root(), root);
+        assert_eq!(tree.leaf_hashes().len(), 4);
+        assert_eq!(tree.leaf_hash_at(0), Some(leaf_sum(data[0])));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sync_tree_generates_valid_proofs_from_concurrent_threads() {
+        const LEAVES: u64 = 100;
+        const THREADS: usize = 8;
+
+        let data = (0..LEAVES).map(u64::to_be_bytes).collect::<Vec<_>>();
+        let tree = SyncBinaryMerkleTree::new();
+        for datum in data.iter() {
+            tree.push(datum);
+        }
+        let root = tree.root();
+
+        std::thread::scope(|scope| {
+            for thread in 0..THREADS {
+                let tree = tree.clone();
+                let data = &data;
+                scope.spawn(move || {
+                    let leaves = (0u64..).zip(data).skip(thread).step_by(THREADS);
+                    for (index, datum) in leaves {
+                        let (proof_root, proof_set) = tree.prove(index).unwrap();
+                        assert_eq!(proof_root, root);
+                        assert!(binary::verify(&root, datum, &proof_set, index, LEAVES));
+                    }
+                });
+            }
+        });
+    }
+    #[cfg(feature = "std")]
+    #[test]
+    fn sync_tree_recovers_from_a_poisoned_lock() {
+        let tree = SyncBinaryMerkleTree::new();
+        tree.push(b"DATA");
+        let root = tree.root();
+
+        let poisoner = tree.clone();
+        let result = std::thread::spawn(move || {
+            let _guard = poisoner.write();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(tree.tree.is_poisoned());
+
+        assert_eq!(tree.root(), root);
+        tree.push(b"MORE DATA");
+        assert_ne!(tree.root(), root);
+    }
 }
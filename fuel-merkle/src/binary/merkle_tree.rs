@@ -1,9 +1,12 @@
 use crate::{
+    VerifyError,
     binary::{
         Node,
         Primitive,
+        SubrootProof,
         empty_sum,
         in_memory::NodesTable,
+        verify,
     },
     common::{
         Bytes32,
@@ -44,6 +47,22 @@ pub enum MerkleTreeError<StorageError> {
 
     #[display(fmt = "the tree is too large")]
     TooLarge,
+
+    #[display(fmt = "leaf index {_0} is out of bounds")]
+    IndexOutOfBounds(u64),
+}
+
+#[cfg(feature = "std")]
+impl<StorageError> std::error::Error for MerkleTreeError<StorageError>
+where
+    StorageError: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MerkleTreeError::StorageError(err) => Some(err),
+            _ => None,
+        }
+    }
 }
 
 impl<StorageError> From<StorageError> for MerkleTreeError<StorageError> {
@@ -52,7 +71,25 @@ impl<StorageError> From<StorageError> for MerkleTreeError<StorageError> {
     }
 }
 
-#[derive(Debug, Clone)]
+impl<StorageError> From<MerkleTreeError<StorageError>> for crate::Error<StorageError> {
+    fn from(err: MerkleTreeError<StorageError>) -> Self {
+        match err {
+            MerkleTreeError::InvalidProofIndex(index) => {
+                VerifyError::InvalidProofIndex(index).into()
+            }
+            MerkleTreeError::LoadError(index) => {
+                crate::Error::MalformedNode(Position::from_in_order_index(index))
+            }
+            MerkleTreeError::StorageError(err) => crate::Error::StorageError(err),
+            MerkleTreeError::TooLarge => VerifyError::TooLarge.into(),
+            MerkleTreeError::IndexOutOfBounds(index) => {
+                VerifyError::InvalidProofIndex(index).into()
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct MerkleTree<TableType, StorageType> {
     storage: StorageType,
     nodes: MerkleRootCalculator,
@@ -60,11 +97,28 @@ pub struct MerkleTree<TableType, StorageType> {
     phantom_table: PhantomData<TableType>,
 }
 
+// The table is only a marker type, so cloning the tree must not require it to
+// implement `Clone`
+impl<TableType, StorageType> Clone for MerkleTree<TableType, StorageType>
+where
+    StorageType: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+            nodes: self.nodes.clone(),
+            leaves_count: self.leaves_count,
+            phantom_table: PhantomData,
+        }
+    }
+}
+
 impl<TableType, StorageType> MerkleTree<TableType, StorageType> {
     pub const fn empty_root() -> &'static Bytes32 {
         empty_sum()
     }
 
+    #[must_use = "calling root() without using the result is a likely bug"]
     pub fn root(&self) -> Bytes32 {
         let mut scratch_storage = StorageMap::<NodesTable>::new();
         let root_node = self
@@ -80,6 +134,45 @@ impl<TableType, StorageType> MerkleTree<TableType, StorageType> {
         self.leaves_count
     }
 
+    /// Verifies that `leaf_data` is the leaf at `leaf_index` of a tree with
+    /// `leaf_count` leaves and the given `root`, using a proof set produced by
+    /// [`prove`](Self::prove). Delegates to [`verify`](crate::binary::verify).
+    pub fn verify_proof(
+        root: &Bytes32,
+        leaf_index: u64,
+        leaf_count: u64,
+        leaf_data: &[u8],
+        proof_set: &ProofSet,
+    ) -> bool {
+        verify(root, &leaf_data, proof_set, leaf_index, leaf_count)
+    }
+
+    /// The height of the tree, i.e. the distance from the root to the leaves
+    /// of the leftmost (and therefore deepest) subtree. A tree with zero or one
+    /// leaves has height 0.
+    pub fn height(&self) -> u32 {
+        self.leaves_count
+            .checked_next_power_of_two()
+            .map_or(u64::BITS, u64::trailing_zeros)
+    }
+
+    /// Returns the number of non-empty nodes at the given `level`, where level
+    /// 0 is the root and level [`height`](Self::height) holds the leaves.
+    /// Levels deeper than the leaves contain no nodes.
+    ///
+    /// This counts node positions covering at least one leaf, which makes it
+    /// suitable for estimating storage requirements per level.
+    pub fn nodes_at_level(&self, level: u32) -> u64 {
+        let Some(node_height) = self.height().checked_sub(level) else {
+            return 0;
+        };
+        // Each node at `node_height` covers 2^node_height leaves; the last
+        // node on the level may only be partially filled.
+        let full = self.leaves_count.checked_shr(node_height).unwrap_or(0);
+        let covered = full.checked_shl(node_height).unwrap_or(0);
+        full.saturating_add(u64::from(covered != self.leaves_count))
+    }
+
     /// The root node is generated by joining all MMR peaks, where a peak is
     /// defined as the head of a balanced subtree. A tree can be composed of a
     /// single balanced subtree, in which case the tree is itself balanced, or
@@ -241,6 +334,38 @@ where
         })
     }
 
+    /// Generates a proof that the leaf at `from_index` belongs to the balanced
+    /// subtree whose root is at `to_level`, where level 0 is the root and
+    /// level [`height`](Self::height) holds the leaves. The proof can be
+    /// checked with [`verify_subroot_proof`](crate::binary::verify_subroot_proof)
+    /// by a client that only knows the subtree root.
+    ///
+    /// Returns `None` if the leaf does not exist, or if the subtree at
+    /// `to_level` containing it is not yet filled with leaves.
+    pub fn subroot_proof(&self, from_index: u64, to_level: u32) -> Option<SubrootProof> {
+        let subroot_height = self.height().checked_sub(to_level)?;
+        let subtree_size = 1u64.checked_shl(subroot_height)?;
+        let subtree_end = from_index
+            .checked_div(subtree_size)?
+            .checked_add(1)?
+            .checked_mul(subtree_size)?;
+        if subtree_end > self.leaves_count {
+            return None
+        }
+
+        let (_, mut proof_set) = self.prove(from_index).ok()?;
+        // The side nodes are ordered from the leaf upwards; the first
+        // `subroot_height` of them lie within the subtree.
+        proof_set.truncate(usize::try_from(subroot_height).ok()?);
+
+        Some(SubrootProof {
+            level: to_level,
+            height: self.height(),
+            proof_set,
+        })
+    }
+
+    #[must_use = "calling prove() without using the result is a likely bug"]
     pub fn prove(
         &self,
         proof_index: u64,
@@ -286,6 +411,33 @@ where
         Ok((root, proof_set))
     }
 
+    /// The hash of the leaf at `index`, or `None` if the tree has no such
+    /// leaf.
+    pub fn leaf_hash_at(
+        &self,
+        index: u64,
+    ) -> Result<Option<Bytes32>, MerkleTreeError<StorageError>> {
+        if index >= self.leaves_count {
+            return Ok(None)
+        }
+        let key = Position::from_leaf_index(index)
+            .ok_or(MerkleTreeError::TooLarge)?
+            .in_order_index();
+        let primitive = self
+            .storage
+            .get(&key)?
+            .ok_or(MerkleTreeError::LoadError(key))?
+            .into_owned();
+        Ok(Some(*Node::from(primitive).hash()))
+    }
+
+    /// The hashes of all the leaves of the tree, in insertion order.
+    pub fn leaf_hashes(&self) -> Result<Vec<Bytes32>, MerkleTreeError<StorageError>> {
+        (0..self.leaves_count)
+            .filter_map(|index| self.leaf_hash_at(index).transpose())
+            .collect()
+    }
+
     pub fn reset(&mut self) {
         self.nodes.clear();
     }
@@ -303,7 +455,20 @@ where
     pub fn push(&mut self, data: &[u8]) -> Result<(), MerkleTreeError<StorageError>> {
         let new_node = Node::create_leaf(self.leaves_count, data)
             .ok_or(MerkleTreeError::TooLarge)?;
+        self.push_leaf(new_node)
+    }
+
+    /// Adds a new leaf node with the given leaf hash to the tree.
+    pub(crate) fn push_leaf_hash(
+        &mut self,
+        hash: Bytes32,
+    ) -> Result<(), MerkleTreeError<StorageError>> {
+        let new_node = Node::create_leaf_with_hash(self.leaves_count, hash)
+            .ok_or(MerkleTreeError::TooLarge)?;
+        self.push_leaf(new_node)
+    }
 
+    fn push_leaf(&mut self, new_node: Node) -> Result<(), MerkleTreeError<StorageError>> {
         // u64 cannot overflow, as memory is finite
         #[allow(clippy::arithmetic_side_effects)]
         {
@@ -322,6 +487,56 @@ where
                 NodeStackPushError::TooLarge => MerkleTreeError::TooLarge,
             })
     }
+
+    /// Replaces the leaf at `index` with a leaf of `data`.
+    ///
+    /// Only the nodes on the path from the leaf up to the peak of its Merkle
+    /// Mountain Range subtree are recomputed and written back to the storage,
+    /// so an update costs `O(log n)` storage reads and writes.
+    /// # WARNING
+    /// This code might modify the storage, and then return an error.
+    pub fn update(
+        &mut self,
+        index: u64,
+        data: &[u8],
+    ) -> Result<(), MerkleTreeError<StorageError>> {
+        if index >= self.leaves_count {
+            return Err(MerkleTreeError::IndexOutOfBounds(index))
+        }
+
+        let mut node = Node::create_leaf(index, data).ok_or(MerkleTreeError::TooLarge)?;
+        loop {
+            self.storage.insert(&node.key(), &(&node).into())?;
+
+            if let Some(peak) = self
+                .nodes
+                .stack_mut()
+                .iter_mut()
+                .find(|peak| peak.position() == node.position())
+            {
+                *peak = node;
+                return Ok(())
+            }
+
+            let position = *node.position();
+            let parent = position.parent().map_err(|_| MerkleTreeError::TooLarge)?;
+            let sibling_key = position
+                .sibling()
+                .map_err(|_| MerkleTreeError::TooLarge)?
+                .in_order_index();
+            let sibling: Node = self
+                .storage
+                .get(&sibling_key)?
+                .ok_or(MerkleTreeError::LoadError(sibling_key))?
+                .into_owned()
+                .into();
+            node = if sibling_key < position.in_order_index() {
+                Node::create_node(parent, &sibling, &node)
+            } else {
+                Node::create_node(parent, &node, &sibling)
+            };
+        }
+    }
 }
 
 /// Calculcate root position from leaf count.
@@ -517,6 +732,7 @@ mod test {
 
         let root = tree.root();
         assert_eq!(root, empty_sum().clone());
+        assert_eq!(root, crate::binary::EMPTY_BINARY_ROOT);
     }
 
     #[test]
@@ -836,6 +1052,117 @@ mod test {
         }
     }
 
+    #[test]
+    fn verify_proof_accepts_proofs_generated_by_prove() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        for (index, datum) in (0u64..).zip(data.iter()) {
+            let (root, proof_set) = tree.prove(index).unwrap();
+            assert!(MerkleTree::<TestTable, ()>::verify_proof(
+                &root, index, 7, datum, &proof_set
+            ));
+        }
+    }
+
+    #[test]
+    fn verify_proof_rejects_tampered_proofs() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        let (root, mut proof_set) = tree.prove(2).unwrap();
+        assert!(!MerkleTree::<TestTable, ()>::verify_proof(
+            &root, 2, 7, data[3], &proof_set
+        ));
+
+        proof_set[0][0] ^= 1;
+        assert!(!MerkleTree::<TestTable, ()>::verify_proof(
+            &root, 2, 7, data[2], &proof_set
+        ));
+    }
+
+    #[test]
+    fn verify_proof_rejects_wrong_index() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        let (root, proof_set) = tree.prove(2).unwrap();
+        assert!(!MerkleTree::<TestTable, ()>::verify_proof(
+            &root, 3, 7, data[2], &proof_set
+        ));
+    }
+
+    #[test]
+    fn subroot_proof_returns_none_for_incomplete_subtrees() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        // The root subtree has 8 leaf slots but only 7 leaves
+        assert_eq!(tree.subroot_proof(0, 0), None);
+        // Leaves 4..8 are not filled
+        assert_eq!(tree.subroot_proof(4, 1), None);
+        // Leaves 6..8 are not filled
+        assert_eq!(tree.subroot_proof(6, 2), None);
+        // Leaf 7 and level 4 do not exist
+        assert_eq!(tree.subroot_proof(7, 3), None);
+        assert_eq!(tree.subroot_proof(0, 4), None);
+
+        let proof = tree.subroot_proof(4, 2).unwrap();
+        assert_eq!(proof.level, 2);
+        assert_eq!(proof.proof_set, [leaf_sum(data[5])]);
+    }
+
+    #[test]
+    fn nodes_at_level_counts_leaves_and_root() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        assert_eq!(tree.nodes_at_level(0), 0);
+
+        for datum in TEST_DATA.iter() {
+            tree.push(datum).unwrap();
+
+            let height = tree.height();
+            assert_eq!(tree.nodes_at_level(height), tree.leaves_count());
+            assert_eq!(tree.nodes_at_level(0), 1);
+            assert_eq!(tree.nodes_at_level(height + 1), 0);
+        }
+    }
+
+    #[test]
+    fn nodes_at_level_returns_width_of_each_level_for_7_leaves() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        for datum in TEST_DATA[0..7].iter() {
+            tree.push(datum).unwrap();
+        }
+
+        assert_eq!(tree.height(), 3);
+        let widths: Vec<_> = (0..=3).map(|level| tree.nodes_at_level(level)).collect();
+        assert_eq!(widths, [1, 2, 4, 7]);
+    }
+
     #[test]
     fn reset_reverts_tree_to_empty_state() {
         let mut storage_map = StorageMap::<TestTable>::new();
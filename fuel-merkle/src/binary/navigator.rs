@@ -0,0 +1,116 @@
+use crate::{
+    binary::{
+        Node,
+        Primitive,
+    },
+    common::{
+        Bytes32,
+        Position,
+    },
+    storage::{
+        Mappable,
+        StorageInspect,
+    },
+};
+
+use core::marker::PhantomData;
+
+/// Read-only access to the nodes of a binary Merkle tree, addressed by their
+/// [`Position`].
+///
+/// The navigator only borrows the storage of the tree, so external tools such
+/// as visualizers, debuggers or custom proof generators can walk the tree
+/// without going through [`MerkleTree`](crate::binary::MerkleTree). Nodes that
+/// are not persisted, like the nodes above the peaks of an unbalanced tree, are
+/// reported as `None`.
+#[derive(Debug)]
+pub struct TreeNavigator<'a, TableType, StorageType> {
+    storage: &'a StorageType,
+    phantom_table: PhantomData<TableType>,
+}
+
+impl<'a, TableType, StorageType> TreeNavigator<'a, TableType, StorageType> {
+    pub fn new(storage: &'a StorageType) -> Self {
+        Self {
+            storage,
+            phantom_table: Default::default(),
+        }
+    }
+}
+
+impl<TableType, StorageType, StorageError> TreeNavigator<'_, TableType, StorageType>
+where
+    TableType: Mappable<Key = u64, Value = Primitive, OwnedValue = Primitive>,
+    StorageType: StorageInspect<TableType, Error = StorageError>,
+{
+    /// The hash of the node at `pos`, or `None` if it is not in the storage.
+    pub fn node(&self, pos: Position) -> Result<Option<Bytes32>, StorageError> {
+        let primitive = self.storage.get(&pos.in_order_index())?;
+        Ok(primitive.map(|primitive| *Node::from(primitive.into_owned()).hash()))
+    }
+
+    /// The hash of the left child of the node at `pos`, or `None` if `pos` is a
+    /// leaf or the child is not in the storage.
+    pub fn left(&self, pos: Position) -> Result<Option<Bytes32>, StorageError> {
+        match pos.left_child() {
+            Ok(child) => self.node(child),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// The hash of the right child of the node at `pos`, or `None` if `pos` is
+    /// a leaf or the child is not in the storage.
+    pub fn right(&self, pos: Position) -> Result<Option<Bytes32>, StorageError> {
+        match pos.right_child() {
+            Ok(child) => self.node(child),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        binary::{
+            MerkleTree,
+            in_memory::NodesTable,
+            leaf_sum,
+            node_sum,
+        },
+        common::StorageMap,
+    };
+    use fuel_merkle_test_helpers::TEST_DATA;
+
+    #[test]
+    fn navigator_walks_the_persisted_nodes_of_the_tree() {
+        let mut storage = StorageMap::<NodesTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        let leaf_0 = leaf_sum(data[0]);
+        let leaf_1 = leaf_sum(data[1]);
+        let leaf_2 = leaf_sum(data[2]);
+        let leaf_3 = leaf_sum(data[3]);
+        let node_1 = node_sum(&leaf_0, &leaf_1);
+        let node_5 = node_sum(&leaf_2, &leaf_3);
+        let node_3 = node_sum(&node_1, &node_5);
+
+        let navigator = TreeNavigator::<NodesTable, _>::new(&storage);
+        let position = Position::from_in_order_index;
+        assert_eq!(navigator.node(position(3)), Ok(Some(node_3)));
+        assert_eq!(navigator.left(position(3)), Ok(Some(node_1)));
+        assert_eq!(navigator.right(position(3)), Ok(Some(node_5)));
+        assert_eq!(navigator.left(position(1)), Ok(Some(leaf_0)));
+        assert_eq!(navigator.right(position(1)), Ok(Some(leaf_1)));
+
+        // Leaves have no children
+        assert_eq!(navigator.left(position(0)), Ok(None));
+        assert_eq!(navigator.right(position(0)), Ok(None));
+        // The root of an unbalanced tree is not persisted
+        assert_eq!(navigator.node(position(7)), Ok(None));
+    }
+}
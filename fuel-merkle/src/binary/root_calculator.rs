@@ -95,6 +95,7 @@ impl MerkleRootCalculator {
             .expect("Tree too large");
     }
 
+    #[must_use = "calling root() without using the result is a likely bug"]
     pub fn root(mut self) -> Bytes32 {
         if self.stack.is_empty() {
             return empty_sum().to_owned()
@@ -112,6 +113,7 @@ impl MerkleRootCalculator {
         self.stack.pop().unwrap().hash().to_owned()
     }
 
+    #[must_use = "calling root_from_iterator() without using the result is a likely bug"]
     pub fn root_from_iterator<I: Iterator<Item = T>, T: AsRef<[u8]>>(
         self,
         iterator: I,
@@ -128,6 +130,10 @@ impl MerkleRootCalculator {
     pub fn stack(&self) -> &Vec<Node> {
         &self.stack
     }
+
+    pub(crate) fn stack_mut(&mut self) -> &mut [Node] {
+        &mut self.stack
+    }
 }
 
 #[cfg(test)]
@@ -9,6 +9,8 @@ use crate::{
     },
 };
 
+use alloc::vec::Vec;
+
 /// Returns None if:
 /// - `num_leaves` is 0
 /// - the result doens't fit in an usize
@@ -130,19 +132,157 @@ pub fn verify<T: AsRef<[u8]>>(
     sum == *root
 }
 
+/// Proof that a leaf belongs to the balanced subtree rooted at a given level of
+/// a binary Merkle tree, produced by
+/// [`MerkleTree::subroot_proof`](crate::binary::MerkleTree::subroot_proof).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubrootProof {
+    /// The level of the subtree root, where level 0 is the root of the tree.
+    pub level: u32,
+    /// The height of the tree, i.e. the level of its leaves.
+    pub height: u32,
+    /// The side nodes from the leaf up to, but excluding, the subtree root.
+    pub proof_set: ProofSet,
+}
+
+/// Verifies that `leaf_data` is the leaf at `leaf_index` of the subtree at
+/// `from_level` whose root is `partial_root`.
+///
+/// The proof must hold one side node per level between `from_level` and the
+/// leaves of a tree of `proof.height` levels, and `leaf_index` must be a leaf
+/// of such a tree. A client that knows the height of the tree should also
+/// check `proof.height` against it.
+pub fn verify_subroot_proof(
+    partial_root: &Bytes32,
+    from_level: u32,
+    leaf_index: u64,
+    leaf_data: &[u8],
+    proof: &SubrootProof,
+) -> bool {
+    if proof.level != from_level {
+        return false
+    }
+    let subtree_height = proof.height.checked_sub(from_level);
+    if subtree_height.and_then(|height| usize::try_from(height).ok())
+        != Some(proof.proof_set.len())
+    {
+        return false
+    }
+    if leaf_index.checked_shr(proof.height).unwrap_or(0) != 0 {
+        return false
+    }
+
+    // Within a balanced subtree, the bits of the leaf index give the side of
+    // the path node at each height, starting from the leaf.
+    let mut index = leaf_index;
+    let mut sum = leaf_sum(leaf_data);
+    for side_node in proof.proof_set.iter() {
+        sum = if index & 1 == 0 {
+            node_sum(&sum, side_node)
+        } else {
+            node_sum(side_node, &sum)
+        };
+        index >>= 1;
+    }
+
+    sum == *partial_root
+}
+
+/// The error returned when decoding a [`BinaryProof`] from bytes whose length
+/// is not a multiple of 32.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+#[display(fmt = "invalid proof length {}; expected a multiple of 32 bytes", _0)]
+pub struct InvalidProofLength(pub usize);
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidProofLength {}
+
+/// An inclusion proof for a leaf of a binary Merkle tree: the side nodes from
+/// the leaf up to the root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BinaryProof(ProofSet);
+
+impl BinaryProof {
+    /// Verifies that `data` is the leaf at `index` of the tree with
+    /// `leaf_count` leaves whose root is `root`. See [`verify`].
+    pub fn verify(
+        &self,
+        root: &Bytes32,
+        index: u64,
+        leaf_count: u64,
+        data: &[u8],
+    ) -> bool {
+        verify(root, &data, &self.0, index, leaf_count)
+    }
+
+    /// The number of side nodes in the proof.
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The side nodes of the proof, ordered from the leaf up to the root.
+    pub fn proof_set(&self) -> &ProofSet {
+        &self.0
+    }
+
+    /// Consumes the proof, returning its side nodes ordered from the leaf up
+    /// to the root.
+    pub fn into_inner(self) -> ProofSet {
+        self.0
+    }
+
+    /// Encodes the proof as the concatenation of its side nodes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.iter().flat_map(|node| node.iter().copied()).collect()
+    }
+}
+
+impl From<ProofSet> for BinaryProof {
+    fn from(proof_set: ProofSet) -> Self {
+        Self(proof_set)
+    }
+}
+
+impl From<BinaryProof> for ProofSet {
+    fn from(proof: BinaryProof) -> Self {
+        proof.0
+    }
+}
+
+impl TryFrom<&[u8]> for BinaryProof {
+    type Error = InvalidProofLength;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let (chunks, remainder) = bytes.as_chunks::<32>();
+        if !remainder.is_empty() {
+            return Err(InvalidProofLength(bytes.len()))
+        }
+        Ok(Self(chunks.to_vec()))
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::verify;
+    use super::{
+        BinaryProof,
+        InvalidProofLength,
+        verify,
+        verify_subroot_proof,
+    };
     use crate::{
         binary::{
             MerkleTree,
             Primitive,
+            leaf_sum,
+            node_sum,
         },
         common::StorageMap,
     };
     use fuel_merkle_test_helpers::TEST_DATA;
     use fuel_storage::Mappable;
 
+    use alloc::vec::Vec;
+
     #[derive(Debug)]
     struct TestTable;
 
@@ -260,4 +400,120 @@ mod test {
         );
         assert!(!verification);
     }
+
+    #[test]
+    fn verify_subroot_proof_returns_true_for_a_balanced_subtree() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..7]; // 7 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        //       03
+        //      /  \
+        //     /    \
+        //   01      05      09
+        //  /  \    /  \    /  \
+        // 00  02  04  06  08  10  12
+        let leaves: Vec<_> = data.iter().map(|datum| leaf_sum(datum)).collect();
+        let node_1 = node_sum(&leaves[0], &leaves[1]);
+        let node_5 = node_sum(&leaves[2], &leaves[3]);
+        let node_3 = node_sum(&node_1, &node_5);
+        let node_9 = node_sum(&leaves[4], &leaves[5]);
+
+        for (index, datum) in (0u64..).zip(data[0..4].iter()) {
+            let proof = tree.subroot_proof(index, 1).unwrap();
+            assert!(verify_subroot_proof(&node_3, 1, index, datum, &proof));
+        }
+
+        let proof = tree.subroot_proof(5, 2).unwrap();
+        assert!(verify_subroot_proof(&node_9, 2, 5, data[5], &proof));
+
+        let proof = tree.subroot_proof(6, 3).unwrap();
+        assert!(proof.proof_set.is_empty());
+        assert!(verify_subroot_proof(&leaves[6], 3, 6, data[6], &proof));
+    }
+
+    #[test]
+    fn verify_subroot_proof_returns_false_for_invalid_proofs() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..8]; // 8 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+        let root = tree.root();
+
+        let proof = tree.subroot_proof(2, 0).unwrap();
+        assert!(verify_subroot_proof(&root, 0, 2, data[2], &proof));
+
+        // Wrong level, index or data
+        assert!(!verify_subroot_proof(&root, 1, 2, data[2], &proof));
+        assert!(!verify_subroot_proof(&root, 0, 3, data[2], &proof));
+        assert!(!verify_subroot_proof(&root, 0, 2, data[3], &proof));
+
+        // Tampered proof set
+        let mut tampered = proof.clone();
+        tampered.proof_set[1][0] ^= 1;
+        assert!(!verify_subroot_proof(&root, 0, 2, data[2], &tampered));
+    }
+
+    #[test]
+    fn verify_subroot_proof_rejects_proof_sets_not_matching_the_level() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..8]; // 8 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+        let leaves: Vec<_> = data.iter().map(|datum| leaf_sum(datum)).collect();
+        let node_1 = node_sum(&leaves[0], &leaves[1]);
+
+        let proof = tree.subroot_proof(0, 2).unwrap();
+        assert_eq!(proof.height, 3);
+        assert!(verify_subroot_proof(&node_1, 2, 0, data[0], &proof));
+
+        // The subtree at level 2 has height 1, so a proof set of another
+        // length is rejected even when it hashes up to the subtree root
+        let mut extended = proof.clone();
+        extended.proof_set.push(node_1);
+        assert!(!verify_subroot_proof(&node_1, 2, 0, data[0], &extended));
+        let mut truncated = proof.clone();
+        truncated.proof_set.clear();
+        assert!(!verify_subroot_proof(&leaves[0], 2, 0, data[0], &truncated));
+
+        // The leaf index is outside a tree of height 3
+        assert!(!verify_subroot_proof(&node_1, 2, 8, data[0], &proof));
+    }
+
+    #[test]
+    fn binary_proof_verifies_and_round_trips_through_bytes() {
+        let mut storage_map = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage_map);
+
+        let data = &TEST_DATA[0..5]; // 5 leaves
+        for datum in data.iter() {
+            tree.push(datum).unwrap();
+        }
+
+        let (root, proof_set) = tree.prove(2).unwrap();
+        let proof = BinaryProof::from(proof_set);
+        assert_eq!(proof.depth(), 3);
+        assert!(proof.verify(&root, 2, 5, data[2]));
+        assert!(!proof.verify(&root, 3, 5, data[2]));
+        assert!(!proof.verify(&root, 2, 5, data[3]));
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), 96);
+        assert_eq!(BinaryProof::try_from(bytes.as_slice()), Ok(proof.clone()));
+        assert_eq!(
+            BinaryProof::try_from(&bytes[..95]),
+            Err(InvalidProofLength(95))
+        );
+        assert_eq!(BinaryProof::try_from(&[0u8; 0][..]), Ok(BinaryProof::default()));
+    }
 }
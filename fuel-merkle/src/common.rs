@@ -1,5 +1,12 @@
+mod bounded_storage_map;
+#[cfg(feature = "concurrent")]
+mod concurrent_storage_map;
+mod cow_storage;
 mod hash;
+#[cfg(feature = "indexmap")]
+mod insertion_order_storage_map;
 mod msb;
+mod mvcc_storage;
 mod path_iterator;
 mod position;
 mod position_path;
@@ -10,16 +17,27 @@ pub(crate) mod error;
 pub(crate) mod node;
 pub(crate) mod path;
 
+pub use bounded_storage_map::{
+    BoundedStorageMap,
+    StorageOverflowError,
+};
+#[cfg(feature = "concurrent")]
+pub use concurrent_storage_map::ConcurrentStorageMap;
+pub use cow_storage::CowStorage;
+#[cfg(feature = "indexmap")]
+pub use insertion_order_storage_map::InsertionOrderStorageMap;
+pub use msb::Msb;
+pub use mvcc_storage::MvccStorage;
 pub use path_iterator::AsPathIterator;
-pub use position::Position;
+pub use position::{
+    ParseError,
+    Position,
+};
+pub use prefix::Prefix;
 pub use storage_map::StorageMap;
 
-pub(crate) use msb::Msb;
 pub(crate) use position_path::PositionPath;
-pub(crate) use prefix::{
-    Prefix,
-    PrefixError,
-};
+pub(crate) use prefix::PrefixError;
 
 pub type Bytes1 = [u8; 1];
 pub type Bytes2 = [u8; 2];
@@ -34,6 +52,10 @@ pub type ProofSet = Vec<Bytes32>;
 pub use hash::{
     sum,
     sum_iter,
+    sum_iter_exact,
+    sum_node_with_prefix,
+    sum_slice,
+    sum_with_prefix,
 };
 
 // Merkle Tree hash of an empty list
@@ -0,0 +1,192 @@
+use crate::{
+    common::StorageMap,
+    storage::{
+        Mappable,
+        StorageInspect,
+        StorageMutate,
+    },
+};
+
+use alloc::borrow::Cow;
+use core::fmt;
+
+/// The error returned by [`BoundedStorageMap`] when an insertion would exceed
+/// its capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+#[display(fmt = "storage overflow; the storage can hold at most {} entries", max)]
+pub struct StorageOverflowError {
+    pub max: usize,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StorageOverflowError {}
+
+/// A [`StorageMap`] holding at most `MAX` entries.
+///
+/// Inserting a new key into a full map fails with [`StorageOverflowError`],
+/// which bounds the memory an adversary can make a tree consume. Replacing the
+/// value of an existing key always succeeds.
+#[derive(Clone)]
+pub struct BoundedStorageMap<Type, const MAX: usize>
+where
+    Type: Mappable,
+{
+    map: StorageMap<Type>,
+}
+
+impl<Type, const MAX: usize> fmt::Debug for BoundedStorageMap<Type, MAX>
+where
+    Type: Mappable,
+    StorageMap<Type>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedStorageMap")
+            .field("map", &self.map)
+            .finish()
+    }
+}
+
+impl<Type, const MAX: usize> Default for BoundedStorageMap<Type, MAX>
+where
+    Type: Mappable,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Type, const MAX: usize> BoundedStorageMap<Type, MAX>
+where
+    Type: Mappable,
+{
+    pub fn new() -> Self {
+        Self {
+            map: StorageMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub const fn capacity(&self) -> usize {
+        MAX
+    }
+}
+
+impl<Type, const MAX: usize> StorageInspect<Type> for BoundedStorageMap<Type, MAX>
+where
+    Type: Mappable,
+    Type::Key: Eq + core::hash::Hash,
+    Type::OwnedKey: Eq + core::hash::Hash + core::borrow::Borrow<Type::Key>,
+{
+    type Error = StorageOverflowError;
+
+    fn get(
+        &self,
+        key: &Type::Key,
+    ) -> Result<Option<Cow<'_, Type::OwnedValue>>, Self::Error> {
+        let Ok(value) = self.map.get(key);
+        Ok(value)
+    }
+
+    fn contains_key(&self, key: &Type::Key) -> Result<bool, Self::Error> {
+        let Ok(contains) = self.map.contains_key(key);
+        Ok(contains)
+    }
+}
+
+impl<Type, const MAX: usize> StorageMutate<Type> for BoundedStorageMap<Type, MAX>
+where
+    Type: Mappable,
+    Type::Key: Eq + core::hash::Hash,
+    Type::OwnedKey: Eq + core::hash::Hash + core::borrow::Borrow<Type::Key>,
+{
+    fn replace(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        if self.map.len() >= MAX && !self.contains_key(key)? {
+            return Err(StorageOverflowError { max: MAX })
+        }
+        let Ok(previous) = self.map.replace(key, value);
+        Ok(previous)
+    }
+
+    fn take(&mut self, key: &Type::Key) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        let Ok(value) = self.map.take(key);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    struct TestKey(usize);
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    struct TestValue(usize);
+
+    struct TestTable;
+
+    impl Mappable for TestTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = TestKey;
+        type OwnedValue = TestValue;
+        type Value = Self::OwnedValue;
+    }
+
+    const MAX: usize = 16;
+
+    #[test]
+    fn test_insert_fails_when_the_map_is_full() {
+        let mut store = BoundedStorageMap::<TestTable, MAX>::new();
+        for i in 0..MAX {
+            store.insert(&TestKey(i), &TestValue(i)).unwrap();
+        }
+        assert_eq!(store.len(), MAX);
+
+        assert_eq!(
+            store.insert(&TestKey(MAX), &TestValue(0)),
+            Err(StorageOverflowError { max: MAX })
+        );
+        assert_eq!(store.len(), MAX);
+        assert_eq!(store.get(&TestKey(MAX)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_replace_existing_key_succeeds_when_the_map_is_full() {
+        let mut store = BoundedStorageMap::<TestTable, MAX>::new();
+        for i in 0..MAX {
+            store.insert(&TestKey(i), &TestValue(i)).unwrap();
+        }
+
+        assert_eq!(
+            store.replace(&TestKey(0), &TestValue(42)),
+            Ok(Some(TestValue(0)))
+        );
+        assert_eq!(
+            store.replace(&TestKey(MAX), &TestValue(0)),
+            Err(StorageOverflowError { max: MAX })
+        );
+    }
+
+    #[test]
+    fn test_insert_succeeds_after_removing_an_entry_from_a_full_map() {
+        let mut store = BoundedStorageMap::<TestTable, MAX>::new();
+        for i in 0..MAX {
+            store.insert(&TestKey(i), &TestValue(i)).unwrap();
+        }
+        store.remove(&TestKey(0)).unwrap();
+
+        assert_eq!(store.insert(&TestKey(MAX), &TestValue(0)), Ok(()));
+        assert_eq!(store.len(), MAX);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    alloc::borrow::ToOwned,
+    storage::{
+        Mappable,
+        StorageInspect,
+        StorageMutate,
+    },
+};
+
+use alloc::borrow::Cow;
+use core::hash::Hash;
+use dashmap::DashMap;
+
+/// An in-memory storage that can be read and written from several threads at
+/// once.
+///
+/// The entries are kept in a [`DashMap`], which locks a single shard of the map
+/// per access. Writes only need a shared reference: `&ConcurrentStorageMap`
+/// implements [`StorageMutate`], so the map can back trees running on different
+/// threads. Values are copied out of the map on reads, because an entry is only
+/// locked while it is looked up.
+#[derive(Debug, Clone)]
+pub struct ConcurrentStorageMap<Type>
+where
+    Type: Mappable,
+    Type::OwnedKey: Eq + Hash,
+{
+    map: DashMap<Type::OwnedKey, Type::OwnedValue>,
+}
+
+impl<Type> Default for ConcurrentStorageMap<Type>
+where
+    Type: Mappable,
+    Type::OwnedKey: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Type> ConcurrentStorageMap<Type>
+where
+    Type: Mappable,
+    Type::OwnedKey: Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            map: Default::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+}
+
+impl<Type> StorageInspect<Type> for ConcurrentStorageMap<Type>
+where
+    Type: Mappable,
+    Type::Key: Eq + Hash,
+    Type::OwnedKey: Eq + Hash + core::borrow::Borrow<Type::Key>,
+{
+    type Error = core::convert::Infallible;
+
+    fn get(
+        &self,
+        key: &Type::Key,
+    ) -> Result<Option<Cow<'_, Type::OwnedValue>>, Self::Error> {
+        let result = self.map.get(key);
+        let value = result.map(|value| Cow::Owned(value.clone()));
+        Ok(value)
+    }
+
+    fn contains_key(&self, key: &Type::Key) -> Result<bool, Self::Error> {
+        let contains = self.map.contains_key(key);
+        Ok(contains)
+    }
+}
+
+impl<Type> StorageMutate<Type> for &ConcurrentStorageMap<Type>
+where
+    Type: Mappable,
+    Type::Key: Eq + Hash,
+    Type::OwnedKey: Eq + Hash + core::borrow::Borrow<Type::Key>,
+{
+    fn replace(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        let previous = self
+            .map
+            .insert(key.to_owned().into(), value.to_owned().into());
+        Ok(previous)
+    }
+
+    fn take(&mut self, key: &Type::Key) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        let value = self.map.remove(key).map(|(_, value)| value);
+        Ok(value)
+    }
+}
+
+impl<Type> StorageMutate<Type> for ConcurrentStorageMap<Type>
+where
+    Type: Mappable,
+    Type::Key: Eq + Hash,
+    Type::OwnedKey: Eq + Hash + core::borrow::Borrow<Type::Key>,
+{
+    fn replace(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        StorageMutate::<Type>::replace(&mut &*self, key, value)
+    }
+
+    fn take(&mut self, key: &Type::Key) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        StorageMutate::<Type>::take(&mut &*self, key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    struct TestKey(u32);
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    struct TestValue(u32);
+
+    struct TestTable;
+
+    impl Mappable for TestTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = TestKey;
+        type OwnedValue = TestValue;
+        type Value = Self::OwnedValue;
+    }
+
+    #[test]
+    fn test_shared_references_write_from_several_threads() {
+        let store = ConcurrentStorageMap::<TestTable>::new();
+
+        std::thread::scope(|scope| {
+            for thread in 0..4u32 {
+                let mut store = &store;
+                scope.spawn(move || {
+                    for i in 0..25u32 {
+                        let key = i.saturating_mul(4).saturating_add(thread);
+                        store.insert(&TestKey(key), &TestValue(key)).unwrap();
+                    }
+                });
+            }
+        });
+
+        assert_eq!(store.len(), 100);
+        for key in 0..100 {
+            let value = store.get(&TestKey(key)).unwrap().unwrap();
+            assert_eq!(value.into_owned(), TestValue(key));
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    common::StorageMap,
+    storage::{
+        Mappable,
+        StorageInspect,
+        StorageMutate,
+    },
+};
+
+use alloc::borrow::Cow;
+// `Arc` needs atomic pointers, which some embedded targets lack. These targets
+// share the map with an `Rc` instead, making the storage `!Send`.
+#[cfg(not(target_has_atomic = "ptr"))]
+use alloc::rc::Rc as Arc;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use core::{
+    borrow::{
+        Borrow,
+        BorrowMut,
+    },
+    fmt,
+};
+
+/// A [`StorageMap`] shared between clones until one of them is modified.
+///
+/// Cloning the storage only increments a reference count. The first write to a
+/// clone whose map is still shared copies the map, after which the clone writes
+/// to its own map. This makes cloning a tree before a speculative update cheap.
+pub struct CowStorage<Type>
+where
+    Type: Mappable,
+{
+    map: Arc<StorageMap<Type>>,
+}
+
+impl<Type> fmt::Debug for CowStorage<Type>
+where
+    Type: Mappable,
+    StorageMap<Type>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CowStorage").field("map", &self.map).finish()
+    }
+}
+
+impl<Type> Clone for CowStorage<Type>
+where
+    Type: Mappable,
+{
+    fn clone(&self) -> Self {
+        Self {
+            map: Arc::clone(&self.map),
+        }
+    }
+}
+
+impl<Type> Default for CowStorage<Type>
+where
+    Type: Mappable,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Type> CowStorage<Type>
+where
+    Type: Mappable,
+{
+    pub fn new() -> Self {
+        Self {
+            map: Arc::new(StorageMap::new()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+}
+
+impl<Type> CowStorage<Type>
+where
+    Type: Mappable,
+    StorageMap<Type>: Clone,
+{
+    /// Returns the map, copying it only if it is still shared with a clone.
+    pub fn into_inner(self) -> StorageMap<Type> {
+        Arc::unwrap_or_clone(self.map)
+    }
+}
+
+impl<Type> Borrow<StorageMap<Type>> for CowStorage<Type>
+where
+    Type: Mappable,
+{
+    fn borrow(&self) -> &StorageMap<Type> {
+        &self.map
+    }
+}
+
+impl<Type> BorrowMut<StorageMap<Type>> for CowStorage<Type>
+where
+    Type: Mappable,
+    StorageMap<Type>: Clone,
+{
+    fn borrow_mut(&mut self) -> &mut StorageMap<Type> {
+        Arc::make_mut(&mut self.map)
+    }
+}
+
+impl<Type> StorageInspect<Type> for CowStorage<Type>
+where
+    Type: Mappable,
+    Type::Key: Eq + core::hash::Hash,
+    Type::OwnedKey: Eq + core::hash::Hash + core::borrow::Borrow<Type::Key>,
+{
+    type Error = core::convert::Infallible;
+
+    fn get(
+        &self,
+        key: &Type::Key,
+    ) -> Result<Option<Cow<'_, Type::OwnedValue>>, Self::Error> {
+        self.map.get(key)
+    }
+
+    fn contains_key(&self, key: &Type::Key) -> Result<bool, Self::Error> {
+        self.map.contains_key(key)
+    }
+}
+
+impl<Type> StorageMutate<Type> for CowStorage<Type>
+where
+    Type: Mappable,
+    Type::Key: Eq + core::hash::Hash,
+    Type::OwnedKey: Eq + core::hash::Hash + core::borrow::Borrow<Type::Key>,
+    StorageMap<Type>: Clone,
+{
+    fn replace(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        Arc::make_mut(&mut self.map).replace(key, value)
+    }
+
+    fn take(&mut self, key: &Type::Key) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        // Don't copy a shared map when there is nothing to remove
+        if !self.map.contains_key(key)? {
+            return Ok(None)
+        }
+        Arc::make_mut(&mut self.map).take(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    struct TestKey(u32);
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    struct TestValue(u32);
+
+    #[derive(Clone)]
+    struct TestTable;
+
+    impl Mappable for TestTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = TestKey;
+        type OwnedValue = TestValue;
+        type Value = Self::OwnedValue;
+    }
+
+    #[test]
+    fn test_clone_shares_the_map_until_it_is_modified() {
+        let mut store = CowStorage::<TestTable>::new();
+        store.insert(&TestKey(0), &TestValue(0)).unwrap();
+
+        let mut clone = store.clone();
+        assert!(Arc::ptr_eq(&store.map, &clone.map));
+
+        clone.insert(&TestKey(1), &TestValue(1)).unwrap();
+        assert!(!Arc::ptr_eq(&store.map, &clone.map));
+        assert_eq!(store.len(), 1);
+        assert_eq!(clone.len(), 2);
+        assert_eq!(store.get(&TestKey(1)).unwrap(), None);
+        assert_eq!(
+            clone.get(&TestKey(1)).unwrap(),
+            Some(Cow::Borrowed(&TestValue(1)))
+        );
+    }
+
+    #[test]
+    fn test_remove_of_a_missing_key_keeps_the_map_shared() {
+        let mut store = CowStorage::<TestTable>::new();
+        store.insert(&TestKey(0), &TestValue(0)).unwrap();
+
+        let mut clone = store.clone();
+        clone.remove(&TestKey(1)).unwrap();
+        assert!(Arc::ptr_eq(&store.map, &clone.map));
+
+        clone.remove(&TestKey(0)).unwrap();
+        assert!(!Arc::ptr_eq(&store.map, &clone.map));
+        assert_eq!(store.len(), 1);
+        assert!(clone.is_empty());
+    }
+}
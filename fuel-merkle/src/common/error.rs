@@ -11,3 +11,12 @@ impl From<PrefixError> for DeserializeError {
         DeserializeError::PrefixError(err)
     }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeserializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DeserializeError::PrefixError(err) => Some(err),
+        }
+    }
+}
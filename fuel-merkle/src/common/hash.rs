@@ -1,15 +1,119 @@
-use super::Bytes32;
+use super::{
+    Bytes32,
+    Prefix,
+    empty_sum_blake3,
+};
 
+#[must_use = "calling sum() without using the result is a likely bug"]
 pub fn sum<T: AsRef<[u8]>>(data: T) -> Bytes32 {
     let hash = blake3::hash(data.as_ref());
-    (*hash.as_bytes()).into()
+    *hash.as_bytes()
 }
 
+#[must_use = "calling sum_iter() without using the result is a likely bug"]
 pub fn sum_iter<I: IntoIterator<Item = T>, T: AsRef<[u8]>>(iterator: I) -> Bytes32 {
     let mut hasher = blake3::Hasher::new();
     for data in iterator {
         hasher.update(data.as_ref());
     }
     let hash = hasher.finalize();
-    (*hash.as_bytes()).into()
+    *hash.as_bytes()
+}
+
+/// Hash the concatenation of the given byte slices.
+///
+/// The output is identical to `sum_iter(data.iter().copied())`.
+#[must_use = "calling sum_slice() without using the result is a likely bug"]
+pub fn sum_slice(data: &[&[u8]]) -> Bytes32 {
+    let mut hasher = blake3::Hasher::new();
+    for slice in data {
+        hasher.update(slice);
+    }
+    let hash = hasher.finalize();
+    *hash.as_bytes()
+}
+
+/// Hash the items of an iterator whose length is known upfront.
+///
+/// The output is identical to [`sum_iter`] for all inputs. BLAKE3 does not
+/// accept an input length hint, so the length is currently only used to
+/// short-circuit empty iterators.
+#[must_use = "calling sum_iter_exact() without using the result is a likely bug"]
+pub fn sum_iter_exact<I: ExactSizeIterator<Item = T>, T: AsRef<[u8]>>(
+    iterator: I,
+) -> Bytes32 {
+    if iterator.len() == 0 {
+        return *empty_sum_blake3()
+    }
+    sum_iter(iterator)
+}
+
+/// Hash `data` in the domain of `prefix`, i.e. `Hash(prefix || data)`.
+///
+/// With [`Prefix::Leaf`], this is the binary Merkle tree
+/// [`leaf_sum`](crate::binary::leaf_sum).
+#[must_use = "calling sum_with_prefix() without using the result is a likely bug"]
+pub fn sum_with_prefix(prefix: Prefix, data: &[u8]) -> Bytes32 {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(prefix.as_ref());
+    hasher.update(data);
+    *hasher.finalize().as_bytes()
+}
+
+/// Hash the pair of child hashes `left` and `right` in the domain of `prefix`,
+/// i.e. `Hash(prefix || left || right)`.
+///
+/// With [`Prefix::Node`], this is the binary Merkle tree
+/// [`node_sum`](crate::binary::node_sum).
+#[must_use = "calling sum_node_with_prefix() without using the result is a likely bug"]
+pub fn sum_node_with_prefix(prefix: Prefix, left: &Bytes32, right: &Bytes32) -> Bytes32 {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(prefix.as_ref());
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sum_iter_exact_matches_sum_iter() {
+        let records: [[u8; 8]; 4] = [[0; 8], [1; 8], [2; 8], [3; 8]];
+
+        for len in 0..=records.len() {
+            let records = &records[..len];
+            assert_eq!(sum_iter_exact(records.iter()), sum_iter(records.iter()));
+        }
+    }
+
+    #[test]
+    fn sum_slice_matches_sum_iter() {
+        let records: [&[u8]; 5] = [b"", b"a", &[0; 64], &[1; 1025], b"last"];
+
+        for start in 0..records.len() {
+            for end in start..=records.len() {
+                let data = &records[start..end];
+                assert_eq!(sum_slice(data), sum_iter(data.iter().copied()));
+            }
+        }
+    }
+
+    #[test]
+    fn prefixed_sums_match_the_binary_tree_sums() {
+        use crate::binary::{
+            leaf_sum,
+            node_sum,
+        };
+
+        let (lhs, rhs) = (leaf_sum(b"lhs"), leaf_sum(b"rhs"));
+
+        assert_eq!(sum_with_prefix(Prefix::Leaf, b"data"), leaf_sum(b"data"));
+        assert_eq!(sum_node_with_prefix(Prefix::Node, &lhs, &rhs), node_sum(&lhs, &rhs));
+        assert_ne!(
+            sum_with_prefix(Prefix::Node, b"data"),
+            sum_with_prefix(Prefix::Leaf, b"data")
+        );
+    }
 }
@@ -0,0 +1,160 @@
+use crate::{
+    alloc::borrow::ToOwned,
+    storage::{
+        Mappable,
+        StorageInspect,
+        StorageMutate,
+    },
+};
+
+use alloc::borrow::Cow;
+use hashbrown::hash_map::DefaultHashBuilder;
+use indexmap::IndexMap;
+
+/// An in-memory storage that remembers the order in which keys were inserted.
+///
+/// Iterating the map yields the entries in the order their keys were first
+/// inserted, which makes it possible to replay the writes to another storage.
+/// Replacing the value of a key keeps the key at its original place, and
+/// removing a key preserves the order of the remaining entries.
+#[derive(Debug, Clone)]
+pub struct InsertionOrderStorageMap<Type>
+where
+    Type: Mappable,
+{
+    map: IndexMap<Type::OwnedKey, Type::OwnedValue, DefaultHashBuilder>,
+}
+
+impl<Type> Default for InsertionOrderStorageMap<Type>
+where
+    Type: Mappable,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Type> InsertionOrderStorageMap<Type>
+where
+    Type: Mappable,
+{
+    pub fn new() -> Self {
+        Self {
+            map: Default::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Iterate over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Type::OwnedKey, &Type::OwnedValue)> {
+        self.map.iter()
+    }
+}
+
+impl<Type> IntoIterator for InsertionOrderStorageMap<Type>
+where
+    Type: Mappable,
+{
+    type Item = (Type::OwnedKey, Type::OwnedValue);
+    type IntoIter = indexmap::map::IntoIter<Type::OwnedKey, Type::OwnedValue>;
+
+    /// Consume the map, yielding the entries in insertion order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+impl<Type> StorageInspect<Type> for InsertionOrderStorageMap<Type>
+where
+    Type: Mappable,
+    Type::Key: Eq + core::hash::Hash,
+    Type::OwnedKey: Eq + core::hash::Hash + core::borrow::Borrow<Type::Key>,
+{
+    type Error = core::convert::Infallible;
+
+    fn get(
+        &self,
+        key: &Type::Key,
+    ) -> Result<Option<Cow<'_, Type::OwnedValue>>, Self::Error> {
+        let result = self.map.get(key);
+        let value = result.map(Cow::Borrowed);
+        Ok(value)
+    }
+
+    fn contains_key(&self, key: &Type::Key) -> Result<bool, Self::Error> {
+        let contains = self.map.contains_key(key);
+        Ok(contains)
+    }
+}
+
+impl<Type> StorageMutate<Type> for InsertionOrderStorageMap<Type>
+where
+    Type: Mappable,
+    Type::Key: Eq + core::hash::Hash,
+    Type::OwnedKey: Eq + core::hash::Hash + core::borrow::Borrow<Type::Key>,
+{
+    fn replace(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        let previous = self
+            .map
+            .insert(key.to_owned().into(), value.to_owned().into());
+        Ok(previous)
+    }
+
+    fn take(&mut self, key: &Type::Key) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        let value = self.map.shift_remove(key);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    struct TestKey(u32);
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    struct TestValue(u32);
+
+    struct TestTable;
+
+    impl Mappable for TestTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = TestKey;
+        type OwnedValue = TestValue;
+        type Value = Self::OwnedValue;
+    }
+
+    #[test]
+    fn test_into_iter_yields_the_entries_in_insertion_order() {
+        let mut store = InsertionOrderStorageMap::<TestTable>::new();
+        for key in [5, 3, 9, 1, 7] {
+            store.insert(&TestKey(key), &TestValue(key)).unwrap();
+        }
+        store.insert(&TestKey(3), &TestValue(30)).unwrap();
+        store.remove(&TestKey(9)).unwrap();
+
+        let entries = store.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                (TestKey(5), TestValue(5)),
+                (TestKey(3), TestValue(30)),
+                (TestKey(1), TestValue(1)),
+                (TestKey(7), TestValue(7)),
+            ]
+        );
+    }
+}
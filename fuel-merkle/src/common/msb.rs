@@ -1,11 +1,26 @@
 type Bit = bool;
 
+/// Bit-level access to a byte string in most-significant-bit-first order.
+///
+/// Bit `0` is the most significant bit of the first byte, bit `7` is its least
+/// significant bit, bit `8` is the most significant bit of the second byte, and
+/// so on. The trees use this order to turn a key into the path from the root
+/// to its leaf, where a `0` bit is a step to the left child and a `1` bit a
+/// step to the right child.
 pub trait Msb {
+    /// The bit at `index`, or `None` if `index` is past the last bit.
     fn get_bit_at_index_from_msb(&self, index: u32) -> Option<Bit>;
+
+    /// The number of leading bits `self` and `other` have in common.
     fn common_prefix_count(&self, other: &[u8]) -> u64;
+
+    /// Whether the bit at `index` is set. Bits past the last bit are unset.
+    fn is_msb_set(&self, index: u32) -> bool {
+        self.get_bit_at_index_from_msb(index).unwrap_or(false)
+    }
 }
 
-impl<const N: usize> Msb for [u8; N] {
+impl Msb for [u8] {
     fn get_bit_at_index_from_msb(&self, index: u32) -> Option<Bit> {
         // The byte that contains the bit
         let byte_index = index / 8;
@@ -37,6 +52,26 @@ impl<const N: usize> Msb for [u8; N] {
     }
 }
 
+impl<const N: usize> Msb for [u8; N] {
+    fn get_bit_at_index_from_msb(&self, index: u32) -> Option<Bit> {
+        self.as_slice().get_bit_at_index_from_msb(index)
+    }
+
+    fn common_prefix_count(&self, other: &[u8]) -> u64 {
+        self.as_slice().common_prefix_count(other)
+    }
+}
+
+impl Msb for u8 {
+    fn get_bit_at_index_from_msb(&self, index: u32) -> Option<Bit> {
+        [*self].get_bit_at_index_from_msb(index)
+    }
+
+    fn common_prefix_count(&self, other: &[u8]) -> u64 {
+        [*self].common_prefix_count(other)
+    }
+}
+
 #[allow(clippy::cast_possible_truncation)]
 #[cfg(test)]
 mod test {
@@ -49,6 +84,29 @@ mod test {
     };
     use core::mem::size_of;
 
+    #[test]
+    fn test_is_msb_set_counts_bits_from_the_most_significant_bit() {
+        assert!(0x80u8.is_msb_set(0));
+        assert!(!0x80u8.is_msb_set(7));
+        assert!(0x01u8.is_msb_set(7));
+        // Bits past the last byte are unset
+        assert!(!0xffu8.is_msb_set(8));
+    }
+
+    #[test]
+    fn test_msb_for_slices_matches_msb_for_arrays() {
+        let bytes: Bytes4 = [0b10101010, 0b11001100, 0b11110000, 0b00001111];
+        let slice: &[u8] = &bytes;
+
+        for i in 0..33 {
+            assert_eq!(
+                slice.get_bit_at_index_from_msb(i),
+                bytes.get_bit_at_index_from_msb(i)
+            );
+        }
+        assert_eq!(slice.common_prefix_count(&[0b10101010, 0b11000000]), 12);
+    }
+
     #[test]
     fn test_msb_for_bytes_1() {
         const NUM_BITS: u32 = size_of::<Bytes1>() as u32 * 8;
@@ -0,0 +1,217 @@
+use crate::storage::{
+    Mappable,
+    StorageInspect,
+    StorageMutate,
+};
+
+use alloc::{
+    borrow::{
+        Cow,
+        ToOwned,
+    },
+    vec::Vec,
+};
+use core::hash::Hash;
+use hashbrown::HashMap;
+
+/// The values written to a key, as `(version, value)` pairs in version order. A
+/// `None` value records a removal.
+type History<Value> = Vec<(u64, Option<Value>)>;
+
+/// A storage that remembers every value written through it, so reads can be
+/// served from a snapshot of the storage at an earlier version.
+///
+/// Each successful write advances a monotonic version counter and records the
+/// new value, or a tombstone for a removal, under that version. The wrapped
+/// storage always holds the latest values. Keys that were never written through
+/// the wrapper are read from the wrapped storage, since they are unchanged at
+/// every version.
+#[derive(Debug, Clone)]
+pub struct MvccStorage<Type, StorageType>
+where
+    Type: Mappable,
+{
+    storage: StorageType,
+    version: u64,
+    history: HashMap<Type::OwnedKey, History<Type::OwnedValue>>,
+}
+
+impl<Type, StorageType> MvccStorage<Type, StorageType>
+where
+    Type: Mappable,
+{
+    pub fn new(storage: StorageType) -> Self {
+        Self {
+            storage,
+            version: 0,
+            history: Default::default(),
+        }
+    }
+
+    /// The version of the latest write, or `0` if nothing was written yet.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn into_inner(self) -> StorageType {
+        self.storage
+    }
+}
+
+impl<Type, StorageType, StorageError> MvccStorage<Type, StorageType>
+where
+    Type: Mappable,
+    Type::Key: Eq + Hash,
+    Type::OwnedKey: Eq + Hash,
+    StorageType: StorageInspect<Type, Error = StorageError>,
+{
+    /// Returns the value of `key` as of `version`, i.e. the most recent value
+    /// written at or before `version`.
+    pub fn read_at_version(
+        &self,
+        version: u64,
+        key: &Type::Key,
+    ) -> Result<Option<Cow<'_, Type::OwnedValue>>, StorageError> {
+        let Some(versions) = self.history.get(key) else {
+            return self.storage.get(key)
+        };
+        let value = versions
+            .iter()
+            .rev()
+            .find(|(written_at, _)| *written_at <= version)
+            .and_then(|(_, value)| value.as_ref());
+        Ok(value.map(Cow::Borrowed))
+    }
+}
+
+impl<Type, StorageType> MvccStorage<Type, StorageType>
+where
+    Type: Mappable,
+    Type::OwnedKey: Eq + Hash,
+{
+    fn record(
+        &mut self,
+        key: &Type::Key,
+        previous: Option<&Type::OwnedValue>,
+        value: Option<Type::OwnedValue>,
+    ) {
+        self.version = self
+            .version
+            .checked_add(1)
+            .expect("The version counter cannot overflow");
+        let versions = self
+            .history
+            .entry(key.to_owned().into())
+            .or_insert_with(|| {
+                // The value the wrapped storage held before the first write
+                // through the wrapper is visible at the initial version
+                let mut versions = Vec::with_capacity(2);
+                versions.push((0, previous.cloned()));
+                versions
+            });
+        versions.push((self.version, value));
+    }
+}
+
+impl<Type, StorageType> StorageInspect<Type> for MvccStorage<Type, StorageType>
+where
+    Type: Mappable,
+    StorageType: StorageInspect<Type>,
+{
+    type Error = StorageType::Error;
+
+    fn get(
+        &self,
+        key: &Type::Key,
+    ) -> Result<Option<Cow<'_, Type::OwnedValue>>, Self::Error> {
+        self.storage.get(key)
+    }
+
+    fn contains_key(&self, key: &Type::Key) -> Result<bool, Self::Error> {
+        self.storage.contains_key(key)
+    }
+}
+
+impl<Type, StorageType> StorageMutate<Type> for MvccStorage<Type, StorageType>
+where
+    Type: Mappable,
+    Type::OwnedKey: Eq + Hash,
+    StorageType: StorageMutate<Type>,
+{
+    fn replace(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        let previous = self.storage.replace(key, value)?;
+        self.record(key, previous.as_ref(), Some(value.to_owned().into()));
+        Ok(previous)
+    }
+
+    fn take(&mut self, key: &Type::Key) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        let previous = self.storage.take(key)?;
+        // Removing a missing key doesn't change the storage
+        if previous.is_some() {
+            self.record(key, previous.as_ref(), None);
+        }
+        Ok(previous)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::StorageMap;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    struct TestKey(u32);
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    struct TestValue(u32);
+
+    struct TestTable;
+
+    impl Mappable for TestTable {
+        type Key = Self::OwnedKey;
+        type OwnedKey = TestKey;
+        type OwnedValue = TestValue;
+        type Value = Self::OwnedValue;
+    }
+
+    fn read(
+        store: &MvccStorage<TestTable, StorageMap<TestTable>>,
+        version: u64,
+        key: u32,
+    ) -> Option<TestValue> {
+        let Ok(value) = store.read_at_version(version, &TestKey(key));
+        value.map(Cow::into_owned)
+    }
+
+    #[test]
+    fn test_read_at_version_returns_the_latest_value_written_at_or_before_it() {
+        let mut inner = StorageMap::<TestTable>::new();
+        inner.insert(&TestKey(0), &TestValue(0)).unwrap();
+        let mut store = MvccStorage::new(inner);
+
+        store.insert(&TestKey(0), &TestValue(1)).unwrap(); // version 1
+        store.insert(&TestKey(1), &TestValue(10)).unwrap(); // version 2
+        store.insert(&TestKey(0), &TestValue(2)).unwrap(); // version 3
+        store.remove(&TestKey(1)).unwrap(); // version 4
+        store.remove(&TestKey(2)).unwrap(); // no-op
+        assert_eq!(store.version(), 4);
+
+        assert_eq!(read(&store, 0, 0), Some(TestValue(0)));
+        assert_eq!(read(&store, 1, 0), Some(TestValue(1)));
+        assert_eq!(read(&store, 2, 0), Some(TestValue(1)));
+        assert_eq!(read(&store, 3, 0), Some(TestValue(2)));
+        assert_eq!(read(&store, u64::MAX, 0), Some(TestValue(2)));
+
+        assert_eq!(read(&store, 1, 1), None);
+        assert_eq!(read(&store, 2, 1), Some(TestValue(10)));
+        assert_eq!(read(&store, 3, 1), Some(TestValue(10)));
+        assert_eq!(read(&store, 4, 1), None);
+
+        assert_eq!(read(&store, 0, 2), None);
+        assert_eq!(store.get(&TestKey(0)).unwrap(), Some(Cow::Owned(TestValue(2))));
+    }
+}
@@ -39,21 +39,52 @@ pub type ChildResult<T: ParentNode> = Result<T, ChildError<T::Key, T::Error>>;
 pub type ChildKeyResult<T: ParentNode> =
     Result<T::ChildKey, ChildError<T::Key, T::Error>>;
 
-#[derive(Debug, Clone, derive_more::Display)]
+#[derive(Debug, Clone)]
 pub enum ChildError<Key, E>
 where
     Key: KeyFormatting,
 {
-    #[display(fmt = "Child with key {} was not found in storage", _0.pretty())]
     ChildNotFound(Key),
-    #[display(fmt = "Node channot have the requested child")]
     ChildCannotExist,
-    #[display(fmt = "Node is a leaf with no children")]
     NodeIsLeaf,
-    #[display(fmt = "{}", _0)]
     Error(E),
 }
 
+// Implemented by hand because the derive would require `Key: Display`, which
+// keys like `[u8; 32]` can't satisfy; they are displayed via `KeyFormatting`.
+impl<Key, E> fmt::Display for ChildError<Key, E>
+where
+    Key: KeyFormatting,
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChildError::ChildNotFound(key) => {
+                write!(f, "Child with key {} was not found in storage", key.pretty())
+            }
+            ChildError::ChildCannotExist => {
+                write!(f, "Node channot have the requested child")
+            }
+            ChildError::NodeIsLeaf => write!(f, "Node is a leaf with no children"),
+            ChildError::Error(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Key, E> std::error::Error for ChildError<Key, E>
+where
+    Key: KeyFormatting + fmt::Debug,
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChildError::Error(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
 impl<Key, E> From<E> for ChildError<Key, E>
 where
     Key: KeyFormatting,
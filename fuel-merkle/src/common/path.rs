@@ -24,7 +24,7 @@ pub trait Path {
 
 impl<T> Path for T
 where
-    T: Msb,
+    T: Msb + ?Sized,
 {
     fn get_instruction(&self, index: u32) -> Option<Side> {
         self.get_bit_at_index_from_msb(index).map(Into::into)
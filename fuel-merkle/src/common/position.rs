@@ -7,6 +7,7 @@ use crate::common::{
         ParentNode,
     },
 };
+use alloc::string::String;
 use core::convert::Infallible;
 
 use super::{
@@ -99,6 +100,9 @@ use super::{
 pub struct Position(u64);
 
 impl Position {
+    /// The length of the longest path accepted by [`Position::from_bit_path`].
+    pub const MAX_BIT_PATH_LEN: usize = 63;
+
     pub fn in_order_index(self) -> u64 {
         self.0
     }
@@ -140,7 +144,6 @@ impl Position {
 
     /// The sibling position.
     /// A position shares the same parent and height as its sibling.
-    #[cfg(test)]
     pub fn sibling(self) -> Result<Self, GetNodeError> {
         #[allow(clippy::arithmetic_side_effects)] // height() <= 64
         let shift = 1u64
@@ -241,6 +244,71 @@ impl Position {
         !self.is_leaf()
     }
 
+    /// Construct the position of the leaf reached by following `path` from the
+    /// root of a tree of height `path.len()`, where a `0` is a step to the left
+    /// child and a `1` a step to the right child.
+    ///
+    /// ```text
+    ///       03
+    ///      /  \
+    ///     /    \
+    ///   01      05
+    ///  /  \    /  \
+    /// 00  02  04  06
+    /// ```
+    ///
+    /// In the tree above, `"10"` is the leaf at in-order index 04. Paths of
+    /// more than 63 steps don't fit in a position.
+    pub fn from_bit_path(path: &str) -> Result<Self, ParseError> {
+        if path.len() > Self::MAX_BIT_PATH_LEN {
+            return Err(ParseError::TooLong(path.len()))
+        }
+        let mut leaf_index = 0u64;
+        for step in path.chars() {
+            let bit = match step {
+                '0' => 0,
+                '1' => 1,
+                _ => return Err(ParseError::InvalidCharacter(step)),
+            };
+            #[allow(clippy::arithmetic_side_effects)] // path.len() <= 63
+            {
+                leaf_index = (leaf_index << 1) | bit;
+            }
+        }
+        Self::from_leaf_index(leaf_index).ok_or(ParseError::TooLong(path.len()))
+    }
+
+    /// The path from the root of a tree of the given `height` down to this
+    /// position, in the format read by [`Position::from_bit_path`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not in the tree of the given `height` whose
+    /// leftmost leaf is the leaf 0.
+    pub fn to_bit_path(&self, height: u32) -> String {
+        let node_height = self.height();
+        let depth = height
+            .checked_sub(node_height)
+            .expect("The position is above the root");
+        // The index of the position among the nodes at its height
+        let index = self
+            .in_order_index()
+            .checked_shr(node_height.saturating_add(1))
+            .unwrap_or(0);
+        assert_eq!(
+            index.checked_shr(depth).unwrap_or(0),
+            0,
+            "The position is outside of the tree"
+        );
+
+        let mut path = String::new();
+        for step in (0..depth).rev() {
+            let bit = index.checked_shr(step).unwrap_or(0) & 1;
+            path.push(if bit == 1 { '1' } else { '0' });
+        }
+        path
+    }
+
     /// Given a leaf position and the total count of leaves in a tree, get the
     /// path from this position to the given leaf position. The shape of the
     /// tree is defined by the `leaves_count` parameter and constrains the
@@ -304,15 +372,34 @@ impl Node for Position {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
 pub enum GetNodeError {
     /// The operation requires a node that can have children.
     /// This is a leaf, and cannot have children.
+    #[display(fmt = "Node is a leaf with no children")]
     IsLeaf,
     /// The requested node cannot exists as it would be out of bounds.
+    #[display(fmt = "Node cannot have the requested child")]
     CannotExist,
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for GetNodeError {}
+
+/// The error returned by [`Position::from_bit_path`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum ParseError {
+    /// The path holds a character other than `0` or `1`.
+    #[display(fmt = "invalid character {:?} in bit path", _0)]
+    InvalidCharacter(char),
+    /// The path is longer than [`Position::MAX_BIT_PATH_LEN`].
+    #[display(fmt = "bit path of length {} is too long", _0)]
+    TooLong(usize),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
 impl ParentNode for Position {
     type ChildKey = u64;
     type Error = Infallible;
@@ -476,4 +563,48 @@ mod test {
         assert_eq!(Position(9).is_node(), true);
         assert_eq!(Position(13).is_node(), true);
     }
+
+    #[test]
+    fn test_from_bit_path() {
+        assert_eq!(Position::from_bit_path(""), Ok(Position(0)));
+        assert_eq!(Position::from_bit_path("0"), Ok(Position(0)));
+        assert_eq!(Position::from_bit_path("1"), Ok(Position(2)));
+        assert_eq!(Position::from_bit_path("10"), Ok(Position(4)));
+        assert_eq!(Position::from_bit_path("0101"), Ok(Position(10)));
+        assert_eq!(Position::from_bit_path("111"), Ok(Position(14)));
+
+        assert_eq!(
+            Position::from_bit_path("01x"),
+            Err(ParseError::InvalidCharacter('x'))
+        );
+        let too_long = "1".repeat(Position::MAX_BIT_PATH_LEN + 1);
+        assert_eq!(
+            Position::from_bit_path(&too_long),
+            Err(ParseError::TooLong(Position::MAX_BIT_PATH_LEN + 1))
+        );
+        let longest = "1".repeat(Position::MAX_BIT_PATH_LEN);
+        assert!(Position::from_bit_path(&longest).is_ok());
+    }
+
+    #[test]
+    fn test_to_bit_path() {
+        assert_eq!(Position(7).to_bit_path(3), "");
+        assert_eq!(Position(3).to_bit_path(3), "0");
+        assert_eq!(Position(11).to_bit_path(3), "1");
+        assert_eq!(Position(9).to_bit_path(3), "10");
+        assert_eq!(Position(10).to_bit_path(3), "101");
+        assert_eq!(Position(10).to_bit_path(4), "0101");
+
+        for path in ["", "0", "1", "0101", "1100", "111"] {
+            let height = u32::try_from(path.len()).unwrap();
+            let position = Position::from_bit_path(path).unwrap();
+            assert_eq!(position.to_bit_path(height), path);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The position is outside of the tree")]
+    fn test_to_bit_path_panics_outside_of_the_tree() {
+        let _ = Position(8).to_bit_path(2);
+    }
 }
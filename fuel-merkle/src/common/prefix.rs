@@ -9,6 +9,15 @@ pub enum Prefix {
     Leaf = LEAF,
 }
 
+impl Prefix {
+    /// The byte prepended to leaf data before hashing a leaf node.
+    /// External verifiers must use the same value.
+    pub const LEAF_BYTE: u8 = LEAF;
+    /// The byte prepended to the child hashes before hashing an internal node.
+    /// External verifiers must use the same value.
+    pub const NODE_BYTE: u8 = NODE;
+}
+
 impl From<Prefix> for u8 {
     fn from(prefix: Prefix) -> Self {
         match prefix {
@@ -33,6 +42,9 @@ pub enum PrefixError {
     InvalidPrefix(u8),
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for PrefixError {}
+
 impl TryFrom<u8> for Prefix {
     type Error = PrefixError;
 
@@ -62,3 +74,21 @@ impl AsRef<[u8; 1]> for Prefix {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Prefix;
+
+    #[test]
+    fn prefix_bytes_match_published_constants() {
+        assert_eq!(Prefix::LEAF_BYTE, 0x00);
+        assert_eq!(Prefix::NODE_BYTE, 0x01);
+
+        let leaf: &[u8] = Prefix::Leaf.as_ref();
+        let node: &[u8] = Prefix::Node.as_ref();
+        assert_eq!(leaf, &[Prefix::LEAF_BYTE]);
+        assert_eq!(node, &[Prefix::NODE_BYTE]);
+        assert_eq!(u8::from(Prefix::Leaf), Prefix::LEAF_BYTE);
+        assert_eq!(u8::from(Prefix::Node), Prefix::NODE_BYTE);
+    }
+}
@@ -44,6 +44,54 @@ where
     pub fn len(&self) -> usize {
         self.map.len()
     }
+
+    /// Returns the approximate number of bytes used by the map, including the
+    /// memory reserved for stored entries and the table's per-bucket control
+    /// bytes. Heap memory owned by the keys or values themselves is not
+    /// counted.
+    pub fn memory_usage_estimate(&self) -> usize {
+        let entry_size =
+            size_of::<Type::OwnedKey>().saturating_add(size_of::<Type::OwnedValue>());
+        // Each bucket stores one control byte alongside the entry.
+        let bucket_size = entry_size.saturating_add(1);
+        self.map
+            .capacity()
+            .saturating_mul(bucket_size)
+            .saturating_add(size_of::<Self>())
+    }
+
+    /// Iterate over the entries in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Type::OwnedKey, &Type::OwnedValue)> {
+        self.map.iter()
+    }
+
+    /// Iterate over the entries in arbitrary order, with mutable access to the
+    /// values.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&Type::OwnedKey, &mut Type::OwnedValue)> {
+        self.map.iter_mut()
+    }
+
+    pub(crate) fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Type::OwnedKey, &mut Type::OwnedValue) -> bool,
+    {
+        self.map.retain(f)
+    }
+}
+
+impl<Type> IntoIterator for StorageMap<Type>
+where
+    Type: Mappable,
+{
+    type Item = (Type::OwnedKey, Type::OwnedValue);
+    type IntoIter = hashbrown::hash_map::IntoIter<Type::OwnedKey, Type::OwnedValue>;
+
+    /// Consume the map, yielding the entries in arbitrary order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
 }
 
 impl<Type> StorageInspect<Type> for StorageMap<Type>
@@ -95,6 +143,7 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    use alloc::vec::Vec;
 
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
     struct TestKey(u32);
@@ -119,6 +168,30 @@ mod test {
 
         assert_eq!(store.get(&key).unwrap(), Some(Cow::Borrowed(&TestValue(0))));
     }
+    #[test]
+    fn test_iter_mut_updates_the_values_in_place() {
+        let mut store = StorageMap::<TestTable>::new();
+        for key in 0..4 {
+            let _ = store.insert(&TestKey(key), &TestValue(key));
+        }
+
+        for (_, value) in store.iter_mut() {
+            value.0 = value.0.saturating_mul(10);
+        }
+
+        let mut entries = store.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+        entries.sort_by_key(|(key, _)| key.0);
+        assert_eq!(
+            entries,
+            vec![
+                (TestKey(0), TestValue(0)),
+                (TestKey(1), TestValue(10)),
+                (TestKey(2), TestValue(20)),
+                (TestKey(3), TestValue(30)),
+            ]
+        );
+    }
+
     #[test]
     fn test_get_returns_none_for_invalid_key() {
         let key = TestKey(0);
@@ -139,6 +212,16 @@ mod test {
         assert_eq!(store.get(&key).unwrap(), Some(Cow::Borrowed(&TestValue(1))));
     }
 
+    #[test]
+    fn test_upsert_returns_the_previous_value_for_given_key() {
+        let key = TestKey(0);
+        let mut store = StorageMap::<TestTable>::new();
+
+        assert_eq!(store.upsert(&key, &TestValue(0)).unwrap(), None);
+        assert_eq!(store.upsert(&key, &TestValue(1)).unwrap(), Some(TestValue(0)));
+        assert_eq!(store.get(&key).unwrap(), Some(Cow::Borrowed(&TestValue(1))));
+    }
+
     #[test]
     fn test_remove_deletes_the_value_for_given_key() {
         let key = TestKey(0);
@@ -175,6 +258,28 @@ mod test {
         assert_eq!(store.contains_key(&key).unwrap(), true);
     }
 
+    #[test]
+    fn test_memory_usage_estimate_returns_struct_size_for_empty_map() {
+        let store = StorageMap::<TestTable>::new();
+
+        assert_eq!(
+            store.memory_usage_estimate(),
+            size_of::<StorageMap<TestTable>>()
+        );
+    }
+
+    #[test]
+    fn test_memory_usage_estimate_grows_with_inserted_entries() {
+        let mut store = StorageMap::<TestTable>::new();
+        let empty_estimate = store.memory_usage_estimate();
+        for i in 0..100 {
+            let _ = store.insert(&TestKey(i), &TestValue(i));
+        }
+
+        let entries_size = store.len() * size_of::<(TestKey, TestValue)>();
+        assert!(store.memory_usage_estimate() >= empty_estimate + entries_size);
+    }
+
     #[test]
     fn test_contains_key_returns_false_for_invalid_key() {
         let invalid_key = TestKey(0);
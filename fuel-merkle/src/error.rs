@@ -0,0 +1,111 @@
+use crate::common::{
+    Bytes32,
+    Position,
+};
+use core::convert::Infallible;
+
+/// The reason a proof could not be produced or checked.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display)]
+pub enum VerifyError {
+    #[display(fmt = "proof index {_0} is not valid")]
+    InvalidProofIndex(u64),
+
+    #[display(fmt = "the tree is too large")]
+    TooLarge,
+
+    #[display(fmt = "a node on the path to the leaf is malformed")]
+    MalformedPath,
+
+    #[display(fmt = "the proof does not lead to the expected root")]
+    RootMismatch,
+}
+
+/// Error returned by the public APIs of the in-memory trees, and the common
+/// error the errors of the storage-backed trees convert into.
+#[derive(Debug, Clone, derive_more::Display)]
+pub enum Error<StorageError> {
+    #[display(fmt = "{}", _0)]
+    StorageError(StorageError),
+
+    #[display(fmt = "{}", _0)]
+    InvalidProof(VerifyError),
+
+    #[display(
+        fmt = "cannot load node with key {}; the key is not found in storage",
+        "hex::encode(_0)"
+    )]
+    KeyNotFound(Bytes32),
+
+    #[display(fmt = "the node at {:?} is malformed or missing", _0)]
+    MalformedNode(Position),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+#[cfg(feature = "std")]
+impl<StorageError> std::error::Error for Error<StorageError>
+where
+    StorageError: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::StorageError(err) => Some(err),
+            Error::InvalidProof(err) => Some(err),
+            Error::KeyNotFound(_) | Error::MalformedNode(_) => None,
+        }
+    }
+}
+
+impl<StorageError> From<VerifyError> for Error<StorageError> {
+    fn from(err: VerifyError) -> Self {
+        Error::InvalidProof(err)
+    }
+}
+
+impl Error<Infallible> {
+    /// Convert the error of an infallible storage into the error of any other
+    /// storage.
+    pub(crate) fn widen<StorageError>(self) -> Error<StorageError> {
+        match self {
+            Error::StorageError(err) => match err {},
+            Error::InvalidProof(err) => Error::InvalidProof(err),
+            Error::KeyNotFound(key) => Error::KeyNotFound(key),
+            Error::MalformedNode(position) => Error::MalformedNode(position),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+    use crate::{
+        common::{
+            PrefixError,
+            error::DeserializeError,
+        },
+        sparse::MerkleTreeError,
+    };
+    use std::{
+        boxed::Box,
+        error::Error as _,
+        string::ToString,
+    };
+
+    #[test]
+    fn errors_expose_the_error_they_wrap_as_their_source() {
+        let err: Box<dyn std::error::Error> =
+            Box::new(MerkleTreeError::<Infallible>::DeserializeError(
+                DeserializeError::PrefixError(PrefixError::InvalidPrefix(7)),
+            ));
+        assert_eq!(err.to_string(), "prefix 7 is not valid");
+
+        let source = err.source().expect("Expected the deserialize error");
+        let source = source.source().expect("Expected the prefix error");
+        assert_eq!(source.to_string(), "prefix 7 is not valid");
+        assert!(source.source().is_none());
+
+        let err = Error::<Infallible>::InvalidProof(VerifyError::TooLarge);
+        assert_eq!(err.source().unwrap().to_string(), "the tree is too large");
+    }
+}
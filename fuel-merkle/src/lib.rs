@@ -1,4 +1,4 @@
-#![cfg_attr(not(feature = "std"), no_std)]
+#![no_std]
 #![allow(clippy::bool_assert_comparison, clippy::identity_op)]
 #![deny(unused_crate_dependencies)]
 #![deny(
@@ -11,11 +11,27 @@
 
 #[cfg_attr(test, macro_use)]
 extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+mod error;
 
 pub mod binary;
 pub mod common;
+#[cfg(feature = "proto")]
+pub mod proto;
 pub mod sparse;
 pub mod storage;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+mod verifier;
+
+pub use binary::TreeNavigator;
+pub use error::{
+    Error,
+    VerifyError,
+};
+pub use verifier::Verifier;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,275 @@
+//! Protobuf encoding of the sparse Merkle tree proofs.
+//!
+//! The message types are the `prost` types generated by the
+//! `fuel-merkle-proto` crate, re-exported here. Conversions from the native
+//! types are infallible; conversions back fail if a message is missing a field
+//! or holds a hash that isn't 32 bytes long.
+
+use crate::{
+    common::{
+        Bytes32,
+        ProofSet as NativeProofSet,
+    },
+    sparse::{
+        self,
+        proof::{
+            ExclusionLeaf,
+            ExclusionLeafData as NativeExclusionLeafData,
+            OptimizedProof,
+        },
+    },
+};
+use alloc::vec::Vec;
+
+pub use fuel_merkle_proto::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display)]
+pub enum ProtoError {
+    #[display(fmt = "expected a 32 bytes value, found {} bytes", _0)]
+    InvalidLength(usize),
+    #[display(fmt = "missing field `{}`", _0)]
+    MissingField(&'static str),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProtoError {}
+
+fn bytes32(bytes: &[u8]) -> Result<Bytes32, ProtoError> {
+    bytes
+        .try_into()
+        .map_err(|_| ProtoError::InvalidLength(bytes.len()))
+}
+
+impl From<sparse::MerkleTreeKey> for MerkleTreeKey {
+    fn from(key: sparse::MerkleTreeKey) -> Self {
+        Self {
+            key: key.into_bytes().to_vec(),
+        }
+    }
+}
+
+/// Decode the path of the leaf addressed by an encoded key.
+///
+/// The bytes of the key are not trusted, so they are not turned back into a
+/// [`sparse::MerkleTreeKey`], which may only hold hashed storage keys. A peer
+/// verifying a received proof derives the key from the storage key it expects
+/// with [`sparse::MerkleTreeKey::new`], and can compare it with this path.
+pub fn key_path(key: &MerkleTreeKey) -> Result<Bytes32, ProtoError> {
+    bytes32(&key.key)
+}
+
+// `ProofSet` is a `Vec` of a foreign type, so the conversions of the proof set
+// are functions rather than `From` implementations
+fn encode_proof_set(proof_set: &NativeProofSet) -> ProofSet {
+    ProofSet {
+        nodes: proof_set.iter().map(|node| node.to_vec()).collect(),
+    }
+}
+
+fn decode_proof_set(proof_set: ProofSet) -> Result<NativeProofSet, ProtoError> {
+    proof_set.nodes.iter().map(|node| bytes32(node)).collect()
+}
+
+impl From<sparse::proof::InclusionProof> for InclusionProof {
+    fn from(proof: sparse::proof::InclusionProof) -> Self {
+        Self {
+            proof_set: Some(encode_proof_set(&proof.proof_set)),
+        }
+    }
+}
+
+impl TryFrom<InclusionProof> for sparse::proof::InclusionProof {
+    type Error = ProtoError;
+
+    fn try_from(proof: InclusionProof) -> Result<Self, Self::Error> {
+        let proof_set = proof
+            .proof_set
+            .ok_or(ProtoError::MissingField("proof_set"))?;
+        Ok(Self {
+            proof_set: decode_proof_set(proof_set)?,
+        })
+    }
+}
+
+impl From<sparse::proof::ExclusionProof> for ExclusionProof {
+    fn from(proof: sparse::proof::ExclusionProof) -> Self {
+        let leaf = match proof.leaf {
+            ExclusionLeaf::Leaf(data) => {
+                exclusion_proof::Leaf::Data(ExclusionLeafData {
+                    leaf_key: data.leaf_key.to_vec(),
+                    leaf_value: data.leaf_value.to_vec(),
+                })
+            }
+            ExclusionLeaf::Placeholder => {
+                exclusion_proof::Leaf::Placeholder(Placeholder {})
+            }
+        };
+        Self {
+            proof_set: Some(encode_proof_set(&proof.proof_set)),
+            leaf: Some(leaf),
+        }
+    }
+}
+
+impl TryFrom<ExclusionProof> for sparse::proof::ExclusionProof {
+    type Error = ProtoError;
+
+    fn try_from(proof: ExclusionProof) -> Result<Self, Self::Error> {
+        let proof_set = proof
+            .proof_set
+            .ok_or(ProtoError::MissingField("proof_set"))?;
+        let leaf = match proof.leaf.ok_or(ProtoError::MissingField("leaf"))? {
+            exclusion_proof::Leaf::Data(data) => {
+                ExclusionLeaf::Leaf(NativeExclusionLeafData {
+                    leaf_key: bytes32(&data.leaf_key)?,
+                    leaf_value: bytes32(&data.leaf_value)?,
+                })
+            }
+            exclusion_proof::Leaf::Placeholder(_) => ExclusionLeaf::Placeholder,
+        };
+        Ok(Self {
+            proof_set: decode_proof_set(proof_set)?,
+            leaf,
+        })
+    }
+}
+
+impl From<OptimizedProof> for Proof {
+    fn from(proof: OptimizedProof) -> Self {
+        let OptimizedProof {
+            placeholders,
+            proof,
+        } = proof;
+        let placeholders = if placeholders == Bytes32::default() {
+            Vec::new()
+        } else {
+            placeholders.to_vec()
+        };
+        let proof = match proof {
+            sparse::proof::Proof::Inclusion(proof) => {
+                proof::Proof::Inclusion(proof.into())
+            }
+            sparse::proof::Proof::Exclusion(proof) => {
+                proof::Proof::Exclusion(proof.into())
+            }
+        };
+        Self {
+            proof: Some(proof),
+            placeholders,
+        }
+    }
+}
+
+impl TryFrom<Proof> for OptimizedProof {
+    type Error = ProtoError;
+
+    fn try_from(proof: Proof) -> Result<Self, Self::Error> {
+        let placeholders = match proof.placeholders.as_slice() {
+            [] => Bytes32::default(),
+            placeholders => bytes32(placeholders)?,
+        };
+        let proof = match proof.proof.ok_or(ProtoError::MissingField("proof"))? {
+            proof::Proof::Inclusion(proof) => {
+                sparse::proof::Proof::Inclusion(proof.try_into()?)
+            }
+            proof::Proof::Exclusion(proof) => {
+                sparse::proof::Proof::Exclusion(proof.try_into()?)
+            }
+        };
+        Ok(Self {
+            placeholders,
+            proof,
+        })
+    }
+}
+
+/// Placeholder side nodes are left out of the encoded proof set, see
+/// [`Proof::optimize`](sparse::proof::Proof::optimize).
+impl From<sparse::proof::Proof> for Proof {
+    fn from(proof: sparse::proof::Proof) -> Self {
+        proof.optimize().into()
+    }
+}
+
+impl TryFrom<Proof> for sparse::proof::Proof {
+    type Error = ProtoError;
+
+    fn try_from(proof: Proof) -> Result<Self, Self::Error> {
+        let proof = OptimizedProof::try_from(proof)?;
+        Ok(proof.decompress())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sparse::zero_sum;
+
+    #[test]
+    fn proof_set_with_a_short_node_is_rejected() {
+        let proof_set = ProofSet {
+            nodes: vec![vec![0; 32], vec![0; 31]],
+        };
+
+        assert_eq!(decode_proof_set(proof_set), Err(ProtoError::InvalidLength(31)));
+    }
+
+    #[test]
+    fn proof_without_a_variant_is_rejected() {
+        let proof = Proof {
+            proof: None,
+            placeholders: vec![],
+        };
+
+        assert_eq!(
+            sparse::proof::Proof::try_from(proof),
+            Err(ProtoError::MissingField("proof"))
+        );
+    }
+
+    #[test]
+    fn placeholder_side_nodes_are_omitted_from_the_encoding() {
+        let mut proof_set = vec![*zero_sum(); 252];
+        proof_set.extend([[0xff; 32]; 4]);
+        let proof = sparse::proof::Proof::Inclusion(sparse::proof::InclusionProof {
+            proof_set,
+        });
+
+        let encoded = Proof::from(proof.clone());
+        let Some(proof::Proof::Inclusion(inclusion)) = &encoded.proof else {
+            panic!("Expected an inclusion proof")
+        };
+        assert_eq!(
+            inclusion.proof_set.as_ref().map(|proof_set| proof_set.nodes.len()),
+            Some(4)
+        );
+        assert_eq!(encoded.placeholders.len(), 32);
+        assert_eq!(sparse::proof::Proof::try_from(encoded), Ok(proof));
+    }
+
+    #[test]
+    fn proof_without_placeholders_has_an_empty_bitmask() {
+        let proof = sparse::proof::Proof::Inclusion(sparse::proof::InclusionProof {
+            proof_set: vec![[0xff; 32]; 3],
+        });
+
+        let encoded = Proof::from(proof.clone());
+        assert!(encoded.placeholders.is_empty());
+        assert_eq!(sparse::proof::Proof::try_from(encoded), Ok(proof));
+    }
+
+    #[test]
+    fn proof_with_short_placeholders_is_rejected() {
+        let proof = Proof {
+            proof: Some(proof::Proof::Inclusion(InclusionProof {
+                proof_set: Some(ProofSet { nodes: vec![] }),
+            })),
+            placeholders: vec![0x80; 31],
+        };
+
+        assert_eq!(
+            sparse::proof::Proof::try_from(proof),
+            Err(ProtoError::InvalidLength(31))
+        );
+    }
+}
@@ -8,13 +8,22 @@ pub use merkle_tree::{
     MerkleTree,
     MerkleTreeError,
     MerkleTreeKey,
+    Node,
+    NodeCache,
 };
 pub use primitive::Primitive;
+#[cfg(feature = "concurrent")]
+pub mod concurrent;
 pub mod in_memory;
 pub mod proof;
 
+pub use proof::verify_proof_batch;
+
 use crate::common::Bytes32;
 
 pub const fn empty_sum() -> &'static Bytes32 {
     zero_sum()
 }
+
+/// The root of an empty sparse Merkle tree.
+pub const EMPTY_SPARSE_ROOT: Bytes32 = *zero_sum();
@@ -0,0 +1,173 @@
+use crate::{
+    Error,
+    common::{
+        Bytes32,
+        ConcurrentStorageMap,
+    },
+    sparse::{
+        self,
+        MerkleTreeError,
+        in_memory::NodesTable,
+        merkle_tree::MerkleTreeKey,
+        proof::Proof,
+    },
+};
+use core::convert::Infallible;
+use std::sync::{
+    Mutex,
+    MutexGuard,
+    PoisonError,
+    RwLock,
+};
+
+type Storage = ConcurrentStorageMap<NodesTable>;
+type SparseMerkleTree<'a> = sparse::MerkleTree<NodesTable, &'a Storage>;
+
+/// An in-memory sparse Merkle tree that can be shared between threads.
+///
+/// The nodes are kept in a [`ConcurrentStorageMap`], so reads such as
+/// [`root`](Self::root) and [`generate_proof`](Self::generate_proof) run
+/// concurrently without blocking each other or the writers.
+///
+/// Writes are serialized through a tree-level `Mutex<()>`: an update loads the
+/// tree at the current root, applies the change and publishes the new root
+/// while holding it, so concurrent writes can't lose each other's changes and
+/// the published root always matches the stored nodes.
+///
+/// An update removes the nodes that are replaced on the path to the leaf. A
+/// read racing with an update may therefore miss a node of the root it
+/// started from; it is then retried while holding the write lock.
+#[derive(Debug)]
+pub struct ConcurrentSparseMerkleTree {
+    storage: Storage,
+    root: RwLock<Bytes32>,
+    write_lock: Mutex<()>,
+}
+
+impl ConcurrentSparseMerkleTree {
+    pub fn new() -> Self {
+        Self {
+            storage: Storage::new(),
+            root: RwLock::new(sparse::EMPTY_SPARSE_ROOT),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    #[must_use = "calling root() without using the result is a likely bug"]
+    pub fn root(&self) -> Bytes32 {
+        *self.root.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn update(&self, key: MerkleTreeKey, data: &[u8]) {
+        self.write(|tree| tree.insert(key, data))
+    }
+
+    pub fn delete(&self, key: MerkleTreeKey) {
+        self.write(|tree| tree.delete(key))
+    }
+
+    #[must_use = "calling generate_proof() without using the result is a likely bug"]
+    pub fn generate_proof(
+        &self,
+        key: &MerkleTreeKey,
+    ) -> Result<Proof, Error<Infallible>> {
+        let generate_proof = |root: &Bytes32| {
+            SparseMerkleTree::load(&self.storage, root)?.generate_proof(key)
+        };
+
+        match generate_proof(&self.root()) {
+            Ok(proof) => Ok(proof),
+            Err(_) => {
+                let _guard = self.lock_writes();
+                Ok(generate_proof(&self.root())?)
+            }
+        }
+    }
+
+    fn lock_writes(&self) -> MutexGuard<'_, ()> {
+        self.write_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write<F>(&self, f: F)
+    where
+        F: FnOnce(&mut SparseMerkleTree<'_>) -> Result<(), MerkleTreeError<Infallible>>,
+    {
+        let _guard = self.lock_writes();
+        let mut tree = SparseMerkleTree::load(&self.storage, &self.root())
+            .expect("`Storage` can't return error");
+        let _ = f(&mut tree);
+        *self.root.write().unwrap_or_else(PoisonError::into_inner) = tree.root();
+    }
+}
+
+impl Default for ConcurrentSparseMerkleTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sparse::in_memory::MerkleTree;
+    use alloc::vec::Vec;
+
+    fn key(index: u32) -> MerkleTreeKey {
+        MerkleTreeKey::new(index.to_be_bytes())
+    }
+
+    #[test]
+    fn concurrent_updates_produce_the_root_of_sequential_updates() {
+        let tree = ConcurrentSparseMerkleTree::new();
+
+        std::thread::scope(|scope| {
+            for thread in 0..4u32 {
+                let tree = &tree;
+                scope.spawn(move || {
+                    for i in 0..25u32 {
+                        let index = i.saturating_mul(4).saturating_add(thread);
+                        tree.update(key(index), &index.to_be_bytes());
+                    }
+                });
+            }
+        });
+
+        let set = (0..100u32).map(|index| (key(index), index.to_be_bytes()));
+        assert_eq!(tree.root(), MerkleTree::from_set(set).root());
+    }
+
+    #[test]
+    fn proofs_can_be_generated_during_updates() {
+        let tree = ConcurrentSparseMerkleTree::new();
+        for index in 0..10u32 {
+            tree.update(key(index), b"DATA");
+        }
+
+        let proofs = std::thread::scope(|scope| {
+            let reader = scope.spawn(|| {
+                (0..10u32)
+                    .map(|index| tree.generate_proof(&key(index)).unwrap())
+                    .collect::<Vec<_>>()
+            });
+            for index in 10..50u32 {
+                tree.update(key(index), b"DATA");
+            }
+            for index in 10..50u32 {
+                tree.delete(key(index));
+            }
+            reader.join().unwrap()
+        });
+        assert!(proofs.iter().all(Proof::is_inclusion));
+
+        let root = tree.root();
+        for index in 0..10u32 {
+            let Proof::Inclusion(proof) = tree.generate_proof(&key(index)).unwrap()
+            else {
+                panic!("Expected an inclusion proof")
+            };
+            assert!(proof.verify(&root, &key(index), b"DATA"));
+        }
+    }
+}
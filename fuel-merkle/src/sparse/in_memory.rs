@@ -1,10 +1,12 @@
 use crate::{
+    Error,
     common::{
         Bytes32,
-        StorageMap,
+        CowStorage,
     },
     sparse::{
         self,
+        NodeCache,
         Primitive,
         merkle_tree::MerkleTreeKey,
         proof::Proof,
@@ -13,18 +15,21 @@ use crate::{
         Mappable,
         StorageInspect,
         StorageMutate,
+        StorageMutateInfallible,
     },
 };
 use alloc::{
     borrow::Cow,
     vec::Vec,
 };
+use core::convert::Infallible;
+use hashbrown::HashMap;
 
 /// The table of the Sparse Merkle tree's nodes. [`MerkleTree`] works with it as a sparse
 /// merkle tree, where the storage key is `Bytes32` and the value is the
 /// [`Buffer`](crate::sparse::Buffer) (raw presentation of the
 /// [`Node`](crate::sparse::Node)).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct NodesTable;
 
 impl Mappable for NodesTable {
@@ -34,10 +39,15 @@ impl Mappable for NodesTable {
     type Value = Self::OwnedValue;
 }
 
-type Storage = StorageMap<NodesTable>;
+type Storage = CowStorage<NodesTable>;
 type SparseMerkleTree = sparse::MerkleTree<NodesTable, Storage>;
 
-#[derive(Debug)]
+/// An in-memory sparse Merkle tree.
+///
+/// The nodes are kept in a [`CowStorage`], so cloning the tree is cheap: the
+/// clone shares the nodes of the original until either of them is updated,
+/// e.g. to apply speculative changes to the clone.
+#[derive(Debug, Clone)]
 pub struct MerkleTree {
     tree: SparseMerkleTree,
 }
@@ -49,6 +59,18 @@ impl MerkleTree {
         }
     }
 
+    /// Create an empty tree limited to `depth` levels below the root. See
+    /// [`sparse::MerkleTree::with_depth`](crate::sparse::MerkleTree::with_depth).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `depth` is 0 or greater than 256.
+    pub fn with_depth(depth: u16) -> Self {
+        Self {
+            tree: SparseMerkleTree::new(Storage::new()).with_depth(depth),
+        }
+    }
+
     /// Build a sparse Merkle tree from a set of key-value pairs. This is
     /// equivalent to creating an empty sparse Merkle tree and sequentially
     /// calling [update](Self::update) for each key-value pair. This constructor
@@ -56,6 +78,7 @@ impl MerkleTree {
     /// preferred approach when the key-values are known upfront. Leaves can be
     /// appended to the returned tree using `update` to further accumulate leaf
     /// data.
+    #[must_use = "from_set() returns the constructed tree"]
     pub fn from_set<I, D>(set: I) -> Self
     where
         I: Iterator<Item = (MerkleTreeKey, D)>,
@@ -66,6 +89,31 @@ impl MerkleTree {
         Self { tree }
     }
 
+    /// Build a sparse Merkle tree from a set of key-value pairs, like
+    /// [from_set](Self::from_set), while reporting progress. `progress` is
+    /// called after every 1000 key-value pairs consumed from `set`, with the
+    /// total number consumed so far.
+    ///
+    /// There is no parallel constructor in this crate, so `progress` is only
+    /// ever called from the calling thread.
+    #[must_use = "from_set_with_progress() returns the constructed tree"]
+    pub fn from_set_with_progress<I, D, F>(set: I, mut progress: F) -> Self
+    where
+        I: Iterator<Item = (MerkleTreeKey, D)>,
+        D: AsRef<[u8]>,
+        F: FnMut(usize),
+    {
+        const PROGRESS_INTERVAL: usize = 1000;
+
+        let set = set.zip(1usize..).map(|(item, count)| {
+            if count % PROGRESS_INTERVAL == 0 {
+                progress(count)
+            }
+            item
+        });
+        Self::from_set(set)
+    }
+
     /// Calculate the sparse Merkle root from a set of key-value pairs. This is
     /// similar to constructing a new tree from a set of key-value pairs using
     /// [from_set](Self::from_set), except this method returns only the root; it
@@ -74,6 +122,7 @@ impl MerkleTree {
     /// not incur the overhead of storage writes. This can be helpful when we
     /// know all the key-values in the set upfront and we will not need to
     /// update the set in the future.
+    #[must_use = "calling root_from_set() without using the result is a likely bug"]
     pub fn root_from_set<I, D>(set: I) -> Bytes32
     where
         I: Iterator<Item = (MerkleTreeKey, D)>,
@@ -132,6 +181,7 @@ impl MerkleTree {
     /// This can be helpful when we know all the key-values in the set upfront
     /// and we need to defer storage writes, such as expensive database inserts,
     /// for batch operations later in the process.
+    #[must_use = "calling nodes_from_set() without using the result is a likely bug"]
     pub fn nodes_from_set<I, D>(set: I) -> (Bytes32, Vec<(Bytes32, Primitive)>)
     where
         I: Iterator<Item = (MerkleTreeKey, D)>,
@@ -194,6 +244,146 @@ impl MerkleTree {
         (root, nodes)
     }
 
+    /// Rebuild a tree from its `root` and the nodes returned by
+    /// [nodes_from_set](Self::nodes_from_set). The nodes are written to the
+    /// tree's storage, after which the tree can be updated as usual. This is the
+    /// inverse of `nodes_from_set` for deferred-write round trips.
+    ///
+    /// Returns an error if `root` is not the empty root and is missing from
+    /// `nodes`, or if its node can't be decoded.
+    pub fn from_nodes(
+        root: Bytes32,
+        nodes: Vec<(Bytes32, Primitive)>,
+    ) -> Result<Self, Error<Infallible>> {
+        let mut storage = Storage::new();
+        for (key, node) in nodes.iter() {
+            StorageMutateInfallible::insert(&mut storage, key, node);
+        }
+        let tree = SparseMerkleTree::load(storage, &root)?;
+        Ok(Self { tree })
+    }
+
+    /// Build a tree from its `root` and the nodes read from a database
+    /// `cursor`. Each node is written to the tree's storage as soon as it is
+    /// read, so the nodes are never collected in an intermediate buffer.
+    ///
+    /// Returns the first error of the cursor as [`Error::StorageError`], or an
+    /// error if `root` is not the empty root and is missing from the nodes.
+    ///
+    /// ```
+    /// use fuel_merkle::sparse::{
+    ///     MerkleTreeKey,
+    ///     in_memory::MerkleTree,
+    /// };
+    ///
+    /// let set = (0u32..10).map(|i| (MerkleTreeKey::new(i.to_be_bytes()), b"DATA"));
+    /// let (root, nodes) = MerkleTree::nodes_from_set(set);
+    ///
+    /// // A database cursor yielding the nodes one at a time
+    /// let cursor = nodes.into_iter().map(Ok::<_, std::io::Error>);
+    ///
+    /// let tree = MerkleTree::from_cursor(root, cursor).unwrap();
+    /// assert_eq!(tree.root(), root);
+    /// ```
+    pub fn from_cursor<C, E>(root: Bytes32, cursor: C) -> Result<Self, Error<E>>
+    where
+        C: Iterator<Item = Result<(Bytes32, Primitive), E>>,
+    {
+        let mut storage = Storage::new();
+        for entry in cursor {
+            let (key, node) = entry.map_err(Error::StorageError)?;
+            StorageMutateInfallible::insert(&mut storage, &key, &node);
+        }
+        let tree = SparseMerkleTree::load(storage, &root)
+            .map_err(|err| Error::<Infallible>::from(err).widen())?;
+        Ok(Self { tree })
+    }
+
+    /// Calculate the sparse Merkle root after applying the `delta` key-value
+    /// pairs to the tree defined by `base_root` and `base_nodes`, such as the
+    /// output of [nodes_from_set](Self::nodes_from_set). Writes go to a
+    /// temporary overlay over `base_nodes`, which is discarded once the root is
+    /// calculated. This can be helpful to validate a state transition
+    /// speculatively, before committing it to storage.
+    ///
+    /// Returns an error if a node required by the update is missing from
+    /// `base_nodes`.
+    pub fn root_from_partial_set<I, D>(
+        base_root: Bytes32,
+        base_nodes: &[(Bytes32, Primitive)],
+        delta: I,
+    ) -> Result<Bytes32, Error<Infallible>>
+    where
+        I: Iterator<Item = (MerkleTreeKey, D)>,
+        D: AsRef<[u8]>,
+    {
+        struct OverlayStorage<'a> {
+            base: HashMap<&'a Bytes32, &'a Primitive>,
+            changes: HashMap<Bytes32, Option<Primitive>>,
+        }
+
+        impl StorageInspect<NodesTable> for OverlayStorage<'_> {
+            type Error = Infallible;
+
+            fn get(
+                &self,
+                key: &Bytes32,
+            ) -> Result<Option<Cow<'_, Primitive>>, Self::Error> {
+                let value = match self.changes.get(key) {
+                    Some(change) => change.as_ref(),
+                    None => self.base.get(key).copied(),
+                };
+                Ok(value.map(Cow::Borrowed))
+            }
+
+            fn contains_key(&self, key: &Bytes32) -> Result<bool, Self::Error> {
+                Ok(self.get(key)?.is_some())
+            }
+        }
+
+        impl StorageMutate<NodesTable> for OverlayStorage<'_> {
+            fn insert(
+                &mut self,
+                key: &Bytes32,
+                value: &Primitive,
+            ) -> Result<(), Self::Error> {
+                self.changes.insert(*key, Some(*value));
+                Ok(())
+            }
+
+            fn replace(
+                &mut self,
+                key: &Bytes32,
+                value: &Primitive,
+            ) -> Result<Option<Primitive>, Self::Error> {
+                let previous = self.get(key)?.map(Cow::into_owned);
+                self.changes.insert(*key, Some(*value));
+                Ok(previous)
+            }
+
+            fn remove(&mut self, key: &Bytes32) -> Result<(), Self::Error> {
+                self.changes.insert(*key, None);
+                Ok(())
+            }
+
+            fn take(&mut self, key: &Bytes32) -> Result<Option<Primitive>, Self::Error> {
+                let previous = self.get(key)?.map(Cow::into_owned);
+                self.changes.insert(*key, None);
+                Ok(previous)
+            }
+        }
+
+        let storage = OverlayStorage {
+            base: base_nodes.iter().map(|(key, node)| (key, node)).collect(),
+            changes: HashMap::new(),
+        };
+        let mut tree = sparse::MerkleTree::<NodesTable, _>::load(storage, &base_root)?;
+        for (key, data) in delta {
+            tree.insert(key, data.as_ref())?;
+        }
+        Ok(tree.root())
+    }
+
     pub fn update(&mut self, key: MerkleTreeKey, data: &[u8]) {
         let _ = self.tree.insert(key, data);
     }
@@ -202,12 +392,66 @@ impl MerkleTree {
         let _ = self.tree.delete(key);
     }
 
+    /// Update the leaf at `key` and return its inclusion proof against the new
+    /// root. See
+    /// [`sparse::MerkleTree::update_and_prove`](crate::sparse::MerkleTree::update_and_prove).
+    pub fn update_and_prove(&mut self, key: MerkleTreeKey, data: &[u8]) -> Proof {
+        self.tree
+            .update_and_prove(key, data)
+            .expect("`Storage` can't return error")
+    }
+
+    #[must_use = "calling root() without using the result is a likely bug"]
     pub fn root(&self) -> Bytes32 {
         self.tree.root()
     }
 
-    pub fn generate_proof(&self, key: &MerkleTreeKey) -> Option<Proof> {
-        self.tree.generate_proof(key).ok()
+    /// Consume the tree, returning its root and an iterator over all of its
+    /// nodes. Like the output of [nodes_from_set](Self::nodes_from_set), the
+    /// nodes can be written to a database and the tree rebuilt with
+    /// [from_nodes](Self::from_nodes).
+    #[must_use = "root_and_nodes() consumes the tree"]
+    pub fn root_and_nodes(self) -> (Bytes32, impl Iterator<Item = (Bytes32, Primitive)>) {
+        let root = self.tree.root();
+        let nodes = self.tree.into_storage().into_inner().into_iter();
+        (root, nodes)
+    }
+
+    #[must_use = "calling generate_proof() without using the result is a likely bug"]
+    pub fn generate_proof(
+        &self,
+        key: &MerkleTreeKey,
+    ) -> Result<Proof, Error<Infallible>> {
+        Ok(self.tree.generate_proof(key)?)
+    }
+
+    /// Generate a proof for `key`, looking up the nodes on its path in `cache`
+    /// first. See
+    /// [`sparse::MerkleTree::generate_proof_with_cache`](crate::sparse::MerkleTree::generate_proof_with_cache).
+    #[must_use = "calling generate_proof_with_cache() without using the result is a likely bug"]
+    pub fn generate_proof_with_cache(
+        &self,
+        key: &MerkleTreeKey,
+        cache: &mut NodeCache,
+    ) -> Result<Proof, Error<Infallible>> {
+        Ok(self.tree.generate_proof_with_cache(key, cache)?)
+    }
+
+    /// The key of the leaf addressed by `key`, against which its proofs are
+    /// verified. See
+    /// [`sparse::MerkleTree::truncate_key`](crate::sparse::MerkleTree::truncate_key).
+    pub fn truncate_key(&self, key: &MerkleTreeKey) -> MerkleTreeKey {
+        let truncated = self.tree.truncate_key(key);
+        // SAFETY: Truncating only clears the trailing bits of a key that was
+        // already safe to use.
+        unsafe { MerkleTreeKey::convert(truncated) }
+    }
+
+    /// Remove all nodes that are no longer reachable from the root, returning
+    /// the number of removed nodes. See
+    /// [`sparse::MerkleTree::compact`](crate::sparse::MerkleTree::compact).
+    pub fn compact(&mut self) -> usize {
+        self.tree.compact()
     }
 }
 
@@ -300,4 +544,238 @@ mod test {
             "2d160499ae72cf3ecefc4a281d1fae5cb0cf413f302d553a99ec387b80d6b696";
         assert_eq!(hex::encode(root), expected_root);
     }
+
+    #[test]
+    fn from_nodes_rebuilds_tree_from_nodes_from_set() {
+        let set = (0u32..10).map(|i| (key(&i.to_be_bytes()), b"DATA"));
+        let (root, nodes) = MerkleTree::nodes_from_set(set.clone());
+
+        let mut tree = MerkleTree::from_nodes(root, nodes).unwrap();
+        assert_eq!(tree.root(), root);
+
+        let mut expected = MerkleTree::from_set(set);
+        tree.update(key(b"\x00\x00\x00\x0a"), b"DATA");
+        tree.delete(key(&3u32.to_be_bytes()));
+        expected.update(key(b"\x00\x00\x00\x0a"), b"DATA");
+        expected.delete(key(&3u32.to_be_bytes()));
+        assert_eq!(tree.root(), expected.root());
+    }
+
+    #[test]
+    fn root_and_nodes_rebuilds_the_tree_with_from_nodes() {
+        let set = (0u32..10).map(|i| (key(&i.to_be_bytes()), b"DATA"));
+        let mut tree = MerkleTree::from_set(set);
+        tree.delete(key(&3u32.to_be_bytes()));
+        let mut expected = tree.clone();
+
+        let (root, nodes) = tree.root_and_nodes();
+        assert_eq!(root, expected.root());
+
+        let mut rebuilt = MerkleTree::from_nodes(root, nodes.collect()).unwrap();
+        assert_eq!(rebuilt.root(), root);
+        rebuilt.delete(key(&4u32.to_be_bytes()));
+        expected.delete(key(&4u32.to_be_bytes()));
+        assert_eq!(rebuilt.root(), expected.root());
+    }
+
+    #[test]
+    fn from_nodes_returns_load_error_for_missing_root() {
+        let root = sum(b"ROOT");
+        let result = MerkleTree::from_nodes(root, Vec::new()).map(|_| ());
+        assert!(matches!(result, Err(Error::KeyNotFound(key)) if key == root));
+
+        let tree = MerkleTree::from_nodes(*sparse::empty_sum(), Vec::new()).unwrap();
+        assert_eq!(tree.root(), *sparse::empty_sum());
+    }
+
+    #[test]
+    fn from_cursor_returns_the_first_cursor_error() {
+        let (root, nodes) = MerkleTree::nodes_from_set(
+            (0u32..10).map(|i| (key(&i.to_be_bytes()), b"DATA")),
+        );
+        let cursor = nodes
+            .into_iter()
+            .map(Ok)
+            .take(3)
+            .chain([Err("cursor closed"), Err("unreachable")]);
+
+        let result = MerkleTree::from_cursor(root, cursor).map(|_| ());
+        assert!(matches!(result, Err(Error::StorageError("cursor closed"))));
+    }
+
+    #[test]
+    fn root_from_partial_set_applies_delta_to_base_tree() {
+        let base = (0u32..10).map(|i| (key(&i.to_be_bytes()), b"DATA"));
+        let delta = [
+            (key(&3u32.to_be_bytes()), b"CHANGE"),
+            (key(&10u32.to_be_bytes()), b"INSERT"),
+        ];
+        let (base_root, base_nodes) = MerkleTree::nodes_from_set(base.clone());
+
+        let root = MerkleTree::root_from_partial_set(
+            base_root,
+            &base_nodes,
+            delta.into_iter(),
+        )
+        .unwrap();
+
+        let mut expected = MerkleTree::from_set(base);
+        for (k, data) in delta {
+            expected.update(k, data);
+        }
+        assert_eq!(root, expected.root());
+        assert_ne!(root, base_root);
+    }
+
+    #[test]
+    fn root_from_partial_set_returns_base_root_for_empty_delta() {
+        let base = (0u32..10).map(|i| (key(&i.to_be_bytes()), b"DATA"));
+        let (base_root, base_nodes) = MerkleTree::nodes_from_set(base);
+
+        let root = MerkleTree::root_from_partial_set(
+            base_root,
+            &base_nodes,
+            core::iter::empty::<(MerkleTreeKey, &[u8])>(),
+        )
+        .unwrap();
+        assert_eq!(root, base_root);
+    }
+
+    #[test]
+    fn with_depth_16_produces_proofs_of_at_most_16_hashes_at_full_capacity() {
+        let mut tree = MerkleTree::with_depth(16);
+        let key = |i: u16| {
+            let mut bytes = [0xffu8; 32];
+            bytes[..2].copy_from_slice(&i.to_be_bytes());
+            MerkleTreeKey::new_without_hash(bytes)
+        };
+        for i in 0..u16::MAX {
+            tree.update(key(i), &i.to_be_bytes());
+        }
+        let root = tree.root();
+
+        for i in (0..u16::MAX).step_by(97) {
+            let Proof::Inclusion(proof) = tree.generate_proof(&key(i)).unwrap() else {
+                panic!("Expected an inclusion proof")
+            };
+            assert!(proof.proof_set.len() <= 16);
+            assert!(proof.verify(&root, &tree.truncate_key(&key(i)), &i.to_be_bytes()));
+        }
+
+        let Proof::Exclusion(proof) = tree.generate_proof(&key(u16::MAX)).unwrap() else {
+            panic!("Expected an exclusion proof")
+        };
+        assert!(proof.proof_set.len() <= 16);
+        assert!(proof.verify(&root, &tree.truncate_key(&key(u16::MAX))));
+    }
+
+    #[test]
+    fn with_depth_addresses_leaves_by_the_leading_bits_of_the_key() {
+        let mut tree = MerkleTree::with_depth(12);
+        tree.update(MerkleTreeKey::new_without_hash([0xab; 32]), b"DATA");
+
+        let mut expected = MerkleTree::new();
+        let mut truncated = [0u8; 32];
+        truncated[0] = 0xab;
+        truncated[1] = 0xa0;
+        expected.update(MerkleTreeKey::new_without_hash(truncated), b"DATA");
+
+        assert_eq!(tree.root(), expected.root());
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_depth_panics_for_zero_depth() {
+        let _ = MerkleTree::with_depth(0);
+    }
+
+    #[test]
+    fn original_is_not_affected_by_updates_of_a_clone() {
+        let mut tree = MerkleTree::new();
+        tree.update(key(b"\x00\x00\x00\x00"), b"DATA");
+        tree.update(key(b"\x00\x00\x00\x01"), b"DATA");
+        let root = tree.root();
+
+        let mut speculative = tree.clone();
+        speculative.update(key(b"\x00\x00\x00\x02"), b"DATA");
+        speculative.delete(key(b"\x00\x00\x00\x00"));
+
+        let mut expected = MerkleTree::new();
+        expected.update(key(b"\x00\x00\x00\x01"), b"DATA");
+        expected.update(key(b"\x00\x00\x00\x02"), b"DATA");
+
+        assert_eq!(tree.root(), root);
+        assert!(tree.generate_proof(&key(b"\x00\x00\x00\x00")).unwrap().is_inclusion());
+        assert_eq!(speculative.root(), expected.root());
+    }
+
+    /// Inserts `key` into an empty tree, then next to a neighbour that only
+    /// differs in the last bit, so the leaves sit at the bottom of the tree.
+    /// Checks the inclusion proofs, and that deleting the keys empties the tree.
+    fn assert_insert_prove_delete(key: Bytes32) {
+        let mut neighbour = key;
+        neighbour[31] ^= 1;
+        let key = MerkleTreeKey::new_without_hash(key);
+        let neighbour = MerkleTreeKey::new_without_hash(neighbour);
+
+        let mut tree = MerkleTree::new();
+        tree.update(key, b"DATA");
+        let root = tree.root();
+        let Proof::Inclusion(proof) = tree.generate_proof(&key).unwrap() else {
+            panic!("Expected an inclusion proof")
+        };
+        assert!(proof.proof_set.is_empty());
+        assert!(proof.verify(&root, &key, b"DATA"));
+        tree.delete(key);
+        assert_eq!(tree.root(), *sparse::empty_sum());
+
+        tree.update(key, b"DATA");
+        tree.update(neighbour, b"NEIGHBOUR");
+        let root = tree.root();
+        for (key, data) in [(key, &b"DATA"[..]), (neighbour, &b"NEIGHBOUR"[..])] {
+            let Proof::Inclusion(proof) = tree.generate_proof(&key).unwrap() else {
+                panic!("Expected an inclusion proof")
+            };
+            assert_eq!(proof.proof_set.len(), 256);
+            assert!(proof.verify(&root, &key, data));
+        }
+
+        tree.delete(neighbour);
+        let mut expected = MerkleTree::new();
+        expected.update(key, b"DATA");
+        assert_eq!(tree.root(), expected.root());
+        tree.delete(key);
+        assert_eq!(tree.root(), *sparse::empty_sum());
+    }
+
+    #[test]
+    fn insert_prove_delete_all_zeros_key() {
+        assert_insert_prove_delete([0x00; 32]);
+    }
+
+    #[test]
+    fn insert_prove_delete_all_ones_key() {
+        assert_insert_prove_delete([0xff; 32]);
+    }
+
+    #[test]
+    fn insert_prove_delete_alternating_bits_key() {
+        let mut key = [0x55; 32];
+        for byte in key.iter_mut().skip(1).step_by(2) {
+            *byte = 0xaa;
+        }
+        assert_insert_prove_delete(key);
+    }
+
+    #[test]
+    fn from_set_with_progress_reports_every_thousand_leaves() {
+        let set = (0u32..2500).map(|i| (key(&i.to_be_bytes()), i.to_be_bytes()));
+
+        let mut reported = Vec::new();
+        let tree =
+            MerkleTree::from_set_with_progress(set.clone(), |count| reported.push(count));
+
+        assert_eq!(reported, vec![1000, 2000]);
+        assert_eq!(tree.root(), MerkleTree::from_set(set).root());
+    }
 }
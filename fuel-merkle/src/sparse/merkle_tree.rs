@@ -1,22 +1,30 @@
 mod branch;
 mod node;
+mod node_cache;
 
 use branch::{
     Branch,
     merge_branches,
 };
+pub use node::Node;
 use node::{
-    Node,
+    SharedNodeCache,
     StorageNode,
     StorageNodeError,
 };
+pub use node_cache::NodeCache;
 
 use crate::{
+    VerifyError,
     common::{
         AsPathIterator,
         Bytes32,
+        Msb,
+        ProofSet,
+        StorageMap,
         error::DeserializeError,
         node::ChildError,
+        path::Path,
     },
     sparse::{
         Primitive,
@@ -26,12 +34,17 @@ use crate::{
             ExclusionLeafData,
             ExclusionProof,
             InclusionProof,
+            PathStep,
             Proof,
+            ProofError,
+            SubpathProof,
         },
+        zero_sum,
     },
     storage::{
         Mappable,
         StorageInspect,
+        StorageInspectInfallible,
         StorageMutate,
     },
 };
@@ -40,6 +53,11 @@ use alloc::{
     vec::Vec,
 };
 use core::{
+    borrow::{
+        Borrow,
+        BorrowMut,
+    },
+    convert::Infallible,
     fmt::{
         Debug,
         Formatter,
@@ -48,6 +66,7 @@ use core::{
     marker::PhantomData,
     ops::Deref,
 };
+use hashbrown::HashSet;
 
 #[derive(Debug, Clone, derive_more::Display)]
 pub enum MerkleTreeError<StorageError> {
@@ -65,6 +84,25 @@ pub enum MerkleTreeError<StorageError> {
 
     #[display(fmt = "{}", _0)]
     ChildError(ChildError<Bytes32, StorageNodeError<StorageError>>),
+
+    #[display(fmt = "{}", _0)]
+    ProofError(ProofError),
+}
+
+#[cfg(feature = "std")]
+impl<StorageError> std::error::Error for MerkleTreeError<StorageError>
+where
+    StorageError: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MerkleTreeError::LoadError(_) => None,
+            MerkleTreeError::StorageError(err) => Some(err),
+            MerkleTreeError::DeserializeError(err) => Some(err),
+            MerkleTreeError::ChildError(err) => Some(err),
+            MerkleTreeError::ProofError(err) => Some(err),
+        }
+    }
 }
 
 impl<StorageError> From<StorageError> for MerkleTreeError<StorageError> {
@@ -73,6 +111,30 @@ impl<StorageError> From<StorageError> for MerkleTreeError<StorageError> {
     }
 }
 
+impl<StorageError> From<MerkleTreeError<StorageError>> for crate::Error<StorageError> {
+    fn from(err: MerkleTreeError<StorageError>) -> Self {
+        match err {
+            MerkleTreeError::LoadError(key)
+            | MerkleTreeError::ChildError(ChildError::ChildNotFound(key)) => {
+                crate::Error::KeyNotFound(key)
+            }
+            MerkleTreeError::StorageError(err)
+            | MerkleTreeError::ChildError(ChildError::Error(
+                StorageNodeError::StorageError(err),
+            )) => crate::Error::StorageError(err),
+            MerkleTreeError::DeserializeError(_)
+            | MerkleTreeError::ChildError(
+                ChildError::ChildCannotExist
+                | ChildError::NodeIsLeaf
+                | ChildError::Error(StorageNodeError::DeserializeError(_)),
+            ) => VerifyError::MalformedPath.into(),
+            MerkleTreeError::ProofError(ProofError::DepthExceeded { .. }) => {
+                VerifyError::TooLarge.into()
+            }
+        }
+    }
+}
+
 /// The safe Merkle tree storage key prevents Merkle tree structure manipulations.
 /// The type contains only one constructor that hashes the storage key.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -87,7 +149,7 @@ impl MerkleTreeKey {
         B: AsRef<[u8]>,
     {
         let hash = blake3::hash(storage_key.as_ref());
-        let hash = (*hash.as_bytes()).into();
+        let hash = *hash.as_bytes();
 
         Self(hash)
     }
@@ -113,6 +175,19 @@ impl MerkleTreeKey {
     {
         unsafe { Self::convert(storage_key) }
     }
+
+    /// Consumes the key and returns the underlying `Bytes32`. Equivalent to
+    /// `Bytes32::from(key)`.
+    pub fn into_bytes(self) -> Bytes32 {
+        self.0
+    }
+
+    /// Returns a reference to the underlying `Bytes32`. This is the same value
+    /// exposed through `Deref` and `AsRef<Bytes32>`; `AsRef<[u8]>` yields the
+    /// same bytes as a slice.
+    pub fn as_bytes(&self) -> &Bytes32 {
+        &self.0
+    }
 }
 
 impl Debug for MerkleTreeKey {
@@ -133,6 +208,12 @@ impl AsRef<[u8]> for MerkleTreeKey {
     }
 }
 
+impl Borrow<Bytes32> for MerkleTreeKey {
+    fn borrow(&self) -> &Bytes32 {
+        &self.0
+    }
+}
+
 impl AsRef<Bytes32> for MerkleTreeKey {
     fn as_ref(&self) -> &Bytes32 {
         &self.0
@@ -154,18 +235,55 @@ impl From<Bytes32> for MerkleTreeKey {
     }
 }
 
+/// A sparse Merkle tree over `StorageType`.
+///
+/// Leaves are addressed by values of type `Key`, which must borrow as the
+/// 32-byte path of the leaf. The default [`MerkleTreeKey`] guarantees that the
+/// path is a hash of the storage key; trees that already work with random
+/// `Bytes32` keys can use `Bytes32` directly, see
+/// [`into_key_type`](Self::into_key_type).
+///
+/// Proofs can be generated through a caller-owned [`NodeCache`] with
+/// [`generate_proof_with_cache`](Self::generate_proof_with_cache), so repeated
+/// proofs over an unchanged part of the tree don't reload the same nodes.
+///
+/// By default leaves are addressed by the full 256 bits of their key. A tree
+/// limited to fewer levels with [`with_depth`](Self::with_depth) only uses the
+/// leading bits of each key, which bounds the length of its proofs. A tree
+/// using the full key can instead refuse to generate proofs longer than a limit
+/// set with [`with_max_proof_depth`](Self::with_max_proof_depth).
 #[derive(Debug)]
-pub struct MerkleTree<TableType, StorageType> {
+pub struct MerkleTree<TableType, StorageType, Key = MerkleTreeKey> {
     root_node: Node,
     storage: StorageType,
+    depth: u32,
+    max_proof_depth: Option<u8>,
     phantom_table: PhantomData<TableType>,
+    phantom_key: PhantomData<Key>,
 }
 
-impl<TableType, StorageType> MerkleTree<TableType, StorageType> {
+impl<TableType, StorageType, Key> Clone for MerkleTree<TableType, StorageType, Key>
+where
+    StorageType: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            root_node: self.root_node.clone(),
+            storage: self.storage.clone(),
+            depth: self.depth,
+            max_proof_depth: self.max_proof_depth,
+            phantom_table: Default::default(),
+            phantom_key: Default::default(),
+        }
+    }
+}
+
+impl<TableType, StorageType, Key> MerkleTree<TableType, StorageType, Key> {
     pub const fn empty_root() -> &'static Bytes32 {
         empty_sum()
     }
 
+    #[must_use = "calling root() without using the result is a likely bug"]
     pub fn root(&self) -> Bytes32 {
         *self.root_node().hash()
     }
@@ -178,6 +296,86 @@ impl<TableType, StorageType> MerkleTree<TableType, StorageType> {
         &self.storage
     }
 
+    /// Reinterpret the tree as one addressed by keys of type `NewKey`. The
+    /// tree itself is unchanged; only the key type accepted by `insert`,
+    /// `delete` and `generate_proof` changes.
+    pub fn into_key_type<NewKey>(self) -> MerkleTree<TableType, StorageType, NewKey> {
+        MerkleTree {
+            root_node: self.root_node,
+            storage: self.storage,
+            depth: self.depth,
+            max_proof_depth: self.max_proof_depth,
+            phantom_table: Default::default(),
+            phantom_key: Default::default(),
+        }
+    }
+
+    /// Limit the tree to `depth` levels below the root.
+    ///
+    /// Only the first `depth` bits of a key address its leaf: keys are
+    /// truncated with [`truncate_key`](Self::truncate_key) by `insert`,
+    /// `update_and_prove`, `delete` and `generate_proof`, so keys sharing
+    /// these bits refer to the same leaf. Leaves are never placed deeper than
+    /// `depth`, so proofs contain at most `depth` hashes and must be verified
+    /// against the truncated key.
+    ///
+    /// The depth is not persisted with the nodes: a tree loaded from storage
+    /// with [`load`](Self::load) must be limited again, with the depth it was
+    /// built with. [`from_set`](Self::from_set) always builds a tree addressed
+    /// by the full keys and must not be limited afterwards; to build a limited
+    /// tree from a set, insert the leaves into a limited empty tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `depth` is 0 or greater than 256.
+    pub fn with_depth(mut self, depth: u16) -> Self {
+        let depth = u32::from(depth);
+        assert!(
+            (1..=Node::max_height()).contains(&depth),
+            "the depth of a sparse Merkle tree must be between 1 and 256"
+        );
+        self.depth = depth;
+        self
+    }
+
+    /// Refuse to generate proofs of leaves more than `max_depth` levels below
+    /// the root.
+    ///
+    /// Adversarial keys sharing a long prefix push their leaves down to depth
+    /// 256, and the proofs of these leaves hold as many hashes. With a limit,
+    /// [`generate_proof`](Self::generate_proof), the methods built on it, and
+    /// the other methods returning proofs, such as `generate_all_proofs`,
+    /// fail with [`ProofError::DepthExceeded`] instead of returning such a
+    /// proof. Unlike [`with_depth`](Self::with_depth), the leaves are still
+    /// addressed by the full key, and the limit only applies to generated
+    /// proofs.
+    pub fn with_max_proof_depth(mut self, max_depth: u8) -> Self {
+        self.max_proof_depth = Some(max_depth);
+        self
+    }
+
+    /// The limit set with [`with_max_proof_depth`](Self::with_max_proof_depth),
+    /// if any.
+    pub fn max_proof_depth(&self) -> Option<u8> {
+        self.max_proof_depth
+    }
+
+    /// The number of levels below the root addressed by the keys of the tree.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Keep the first [`depth`](Self::depth) bits of `key`, clearing the
+    /// remaining ones. This is the path of the leaf addressed by `key`.
+    pub fn truncate_key(&self, key: &Bytes32) -> Bytes32 {
+        let mut truncated = *key;
+        for (offset, byte) in (0u32..).step_by(8).zip(truncated.iter_mut()) {
+            let kept_bits = self.depth.saturating_sub(offset);
+            *byte &= !0xffu8.checked_shr(kept_bits).unwrap_or(0);
+        }
+        truncated
+    }
+
     fn root_node(&self) -> &Node {
         &self.root_node
     }
@@ -197,7 +395,10 @@ where
         Self {
             root_node: Node::create_placeholder(),
             storage,
+            depth: Node::max_height(),
+            max_proof_depth: None,
             phantom_table: Default::default(),
+            phantom_key: Default::default(),
         }
     }
 
@@ -218,30 +419,63 @@ where
                     .try_into()
                     .map_err(MerkleTreeError::DeserializeError)?,
                 storage,
+                    depth: Node::max_height(),
+                max_proof_depth: None,
                 phantom_table: Default::default(),
+                phantom_key: Default::default(),
             };
             Ok(tree)
         }
     }
+}
 
+impl<TableType, StorageType, StorageError, Key> MerkleTree<TableType, StorageType, Key>
+where
+    TableType: Mappable<Key = Bytes32, Value = Primitive, OwnedValue = Primitive>,
+    StorageType: StorageInspect<TableType, Error = StorageError>,
+{
     fn path_set(
         &self,
         leaf_key: &Bytes32,
     ) -> Result<(Vec<Node>, Vec<Bytes32>), MerkleTreeError<StorageError>> {
+        self.limited_path_set(leaf_key, None, None)
+    }
+
+    /// Like [`path_set`](Self::path_set), but stops walking down the tree and
+    /// fails with [`ProofError::DepthExceeded`] once the path holds more than
+    /// `max_depth` side nodes. Nodes are looked up in `cache`, if any, before
+    /// being loaded from storage.
+    fn limited_path_set(
+        &self,
+        leaf_key: &Bytes32,
+        max_depth: Option<u8>,
+        cache: Option<&mut NodeCache>,
+    ) -> Result<(Vec<Node>, Vec<Bytes32>), MerkleTreeError<StorageError>> {
+        // The path iterator yields the root first, which isn't a side node, so
+        // `max_depth` side nodes take `max_depth + 1` steps. One more step is
+        // enough to know the limit is exceeded.
+        let max_steps =
+            max_depth.map_or(usize::MAX, |depth| usize::from(depth).saturating_add(1));
         let root_node = self.root_node().clone();
-        let root_storage_node = StorageNode::new(&self.storage, root_node);
+        let cache = cache.map(|cache| SharedNodeCache::new(&mut *cache));
+        let root_storage_node =
+            StorageNode::with_cache(&self.storage, cache.as_ref(), root_node);
+        let path = root_storage_node
+            .as_path_iter(leaf_key)
+            .take(max_steps.saturating_add(1))
+            .map(|(path_node, side_node)| {
+                Ok((
+                    path_node.map_err(MerkleTreeError::ChildError)?.into_node(),
+                    side_node.map_err(MerkleTreeError::ChildError)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, MerkleTreeError<StorageError>>>()?;
+        if let Some(max_depth) = max_depth.filter(|_| path.len() > max_steps) {
+            let err = ProofError::DepthExceeded { max_depth };
+            return Err(MerkleTreeError::ProofError(err))
+        }
         let (mut path_nodes, mut side_nodes): (Vec<Node>, Vec<Bytes32>) =
-            root_storage_node
-                .as_path_iter(leaf_key)
-                .map(|(path_node, side_node)| {
-                    Ok((
-                        path_node.map_err(MerkleTreeError::ChildError)?.into_node(),
-                        side_node.map_err(MerkleTreeError::ChildError)?,
-                    ))
-                })
-                .collect::<Result<Vec<_>, MerkleTreeError<StorageError>>>()?
-                .into_iter()
-                .unzip();
+            path.into_iter().unzip();
         path_nodes.reverse();
         side_nodes.reverse();
         side_nodes.pop(); // The last element in the side nodes list is the
@@ -249,6 +483,41 @@ where
 
         Ok((path_nodes, side_nodes))
     }
+
+    /// Check that the nodes in the storage hash up to the root of the tree.
+    ///
+    /// Every node reachable from the root is rehashed from its stored content
+    /// and compared with the hash its parent refers to it by, starting with the
+    /// root itself. This is equivalent to recomputing the root from the leaves,
+    /// and returns `false` if a node is missing, can't be decoded, or doesn't
+    /// match its hash, which indicates a corrupted storage.
+    pub fn verify_root(&self) -> Result<bool, MerkleTreeError<StorageError>> {
+        let mut pending = Vec::new();
+        if !self.root_node().is_placeholder() {
+            pending.push(self.root());
+        }
+
+        while let Some(key) = pending.pop() {
+            let Some(primitive) = self.storage.get(&key)? else {
+                return Ok(false)
+            };
+            let Ok(node) = Node::try_from(primitive.into_owned()) else {
+                return Ok(false)
+            };
+            if *node.hash() != key {
+                return Ok(false)
+            }
+            if node.is_node() {
+                for child in [node.left_child_key(), node.right_child_key()] {
+                    if child != zero_sum() {
+                        pending.push(*child);
+                    }
+                }
+            }
+        }
+
+        Ok(true)
+    }
 }
 
 impl<TableType, StorageType, StorageError> MerkleTree<TableType, StorageType>
@@ -263,6 +532,7 @@ where
     /// preferred approach when the key-values are known upfront. Leaves can be
     /// appended to the returned tree using `update` to further accumulate leaf
     /// data.
+    #[must_use = "from_set() returns the constructed tree"]
     pub fn from_set<B, I, D>(
         mut storage: StorageType,
         set: I,
@@ -397,24 +667,35 @@ where
         let tree = Self {
             root_node: node,
             storage,
+            depth: Node::max_height(),
+            max_proof_depth: None,
             phantom_table: Default::default(),
+            phantom_key: Default::default(),
         };
         Ok(tree)
     }
+}
 
+impl<TableType, StorageType, StorageError, Key> MerkleTree<TableType, StorageType, Key>
+where
+    TableType: Mappable<Key = Bytes32, Value = Primitive, OwnedValue = Primitive>,
+    StorageType: StorageMutate<TableType, Error = StorageError>,
+    Key: Borrow<Bytes32>,
+{
     pub fn insert(
         &mut self,
-        key: MerkleTreeKey,
+        key: Key,
         data: &[u8],
     ) -> Result<(), MerkleTreeError<StorageError>> {
-        let leaf_node = Node::create_leaf(key.as_ref(), data);
+        let key = &self.truncate_key(key.borrow());
+        let leaf_node = Node::create_leaf(key, data);
         self.storage
             .insert(leaf_node.hash(), &leaf_node.as_ref().into())?;
 
         if self.root_node().is_placeholder() {
             self.set_root_node(leaf_node);
         } else {
-            let (path_nodes, side_nodes) = self.path_set(key.as_ref())?;
+            let (path_nodes, side_nodes) = self.path_set(key)?;
             self.update_with_path_set(
                 &leaf_node,
                 path_nodes.as_slice(),
@@ -425,21 +706,78 @@ where
         Ok(())
     }
 
-    pub fn delete(
+    /// Insert `data` at `key` and return the inclusion proof of the updated
+    /// leaf against the new root.
+    ///
+    /// This is equivalent to calling [`insert`](Self::insert) followed by
+    /// [`generate_proof`](Self::generate_proof), but the proof is assembled from
+    /// the path set fetched for the update instead of a second traversal. If
+    /// the proof would exceed the limit set with
+    /// [`with_max_proof_depth`](Self::with_max_proof_depth), this fails with
+    /// [`ProofError::DepthExceeded`] and leaves the tree unchanged.
+    pub fn update_and_prove(
         &mut self,
-        key: MerkleTreeKey,
-    ) -> Result<(), MerkleTreeError<StorageError>> {
+        key: Key,
+        data: &[u8],
+    ) -> Result<Proof, MerkleTreeError<StorageError>> {
+        let key = &self.truncate_key(key.borrow());
+        let leaf_node = Node::create_leaf(key, data);
+
+        let mut proof_set = ProofSet::new();
+        if self.root_node().is_placeholder() {
+            self.storage
+                .insert(leaf_node.hash(), &leaf_node.as_ref().into())?;
+            self.set_root_node(leaf_node);
+        } else {
+            let (path_nodes, side_nodes) =
+                self.limited_path_set(key, self.max_proof_depth, None)?;
+
+            // A new leaf is merged with the closest existing leaf, followed by
+            // placeholders up to the previous depth of that leaf. Both become
+            // side nodes of the new leaf, below the existing side nodes. See
+            // `update_with_path_set`.
+            let actual_leaf_node = &path_nodes[0];
+            if actual_leaf_node.leaf_key() != key && !actual_leaf_node.is_placeholder() {
+                proof_set.push(*actual_leaf_node.hash());
+                let ancestor_depth = leaf_node.common_path_length(actual_leaf_node);
+                #[allow(clippy::cast_possible_truncation)] // Key is 32 bytes
+                let placeholders_count =
+                    (ancestor_depth as usize).saturating_sub(side_nodes.len());
+                proof_set.extend(iter::repeat_n(*zero_sum(), placeholders_count));
+            }
+            proof_set.extend_from_slice(&side_nodes);
+            if let Some(max_depth) = self
+                .max_proof_depth
+                .filter(|max_depth| proof_set.len() > usize::from(*max_depth))
+            {
+                let err = ProofError::DepthExceeded { max_depth };
+                return Err(MerkleTreeError::ProofError(err))
+            }
+
+            self.storage
+                .insert(leaf_node.hash(), &leaf_node.as_ref().into())?;
+            self.update_with_path_set(
+                &leaf_node,
+                path_nodes.as_slice(),
+                side_nodes.as_slice(),
+            )?;
+        }
+
+        Ok(Proof::Inclusion(InclusionProof { proof_set }))
+    }
+
+    pub fn delete(&mut self, key: Key) -> Result<(), MerkleTreeError<StorageError>> {
+        let key = &self.truncate_key(key.borrow());
         if self.root() == *Self::empty_root() {
             // The zero root signifies that all leaves are empty, including the
             // given key.
             return Ok(())
         }
 
-        let (path_nodes, side_nodes): (Vec<Node>, Vec<_>) =
-            self.path_set(key.as_ref())?;
+        let (path_nodes, side_nodes): (Vec<Node>, Vec<_>) = self.path_set(key)?;
 
         match path_nodes.first() {
-            Some(node) if *node.leaf_key() == key.as_ref() => {
+            Some(node) if node.leaf_key() == key => {
                 self.delete_with_path_set(path_nodes.as_slice(), side_nodes.as_slice())?;
             }
             _ => {}
@@ -448,6 +786,103 @@ where
         Ok(())
     }
 
+    /// Delete every leaf whose key starts with the first `prefix_bits` bits of
+    /// `prefix_bytes`, returning the number of deleted leaves. Bits past the
+    /// end of `prefix_bytes` are ignored.
+    ///
+    /// The subtree rooted at the end of the prefix bit-path is removed at once
+    /// and the path above it is rebuilt a single time, instead of once per
+    /// deleted leaf as with repeated calls to [`delete`](Self::delete).
+    pub fn bulk_delete_prefix(
+        &mut self,
+        prefix_bytes: &[u8],
+        prefix_bits: u8,
+    ) -> Result<usize, MerkleTreeError<StorageError>> {
+        let prefix_bits =
+            usize::from(prefix_bits).min(prefix_bytes.len().saturating_mul(8));
+
+        // The nodes on the prefix bit-path and their side nodes, from the root
+        // downwards
+        let mut path_nodes = Vec::new();
+        let mut side_nodes = Vec::new();
+        let mut subtree = self.root_node().clone();
+        for depth in 0..prefix_bits {
+            if subtree.is_placeholder() {
+                return Ok(0)
+            }
+            if subtree.is_leaf() {
+                // A leaf placed above the end of the prefix bit-path is the only
+                // leaf of its subtree, and may or may not share the prefix.
+                let common = subtree.leaf_key().common_prefix_count(prefix_bytes);
+                if common < prefix_bits as u64 {
+                    return Ok(0)
+                }
+                break
+            }
+            // `depth` is below `prefix_bits`, which is clamped to the length of
+            // `prefix_bytes` in bits
+            #[allow(clippy::arithmetic_side_effects)]
+            let goes_right = prefix_bytes[depth / 8] & (0x80 >> (depth % 8)) != 0;
+            let (child, side_node) = if goes_right {
+                (*subtree.right_child_key(), *subtree.left_child_key())
+            } else {
+                (*subtree.left_child_key(), *subtree.right_child_key())
+            };
+            if child == *zero_sum() {
+                return Ok(0)
+            }
+            path_nodes.push(subtree);
+            side_nodes.push(side_node);
+            subtree = self.load_node(&child)?;
+        }
+        if subtree.is_placeholder() {
+            return Ok(0)
+        }
+        path_nodes.push(subtree);
+
+        // An ancestor whose other child is a placeholder only holds the leaves
+        // of the subtree, so it is removed along with the subtree.
+        while side_nodes.last() == Some(zero_sum()) {
+            side_nodes.pop();
+            path_nodes.pop();
+        }
+        let subtree = path_nodes.last().expect("The subtree is on the path").clone();
+
+        let mut leaves_count = 0usize;
+        let mut pending = Vec::new();
+        pending.push(subtree);
+        while let Some(node) = pending.pop() {
+            if node.is_leaf() {
+                #[allow(clippy::arithmetic_side_effects)] // Bounded by the storage
+                {
+                    leaves_count += 1;
+                }
+            } else {
+                for child in [node.left_child_key(), node.right_child_key()] {
+                    if child != zero_sum() {
+                        pending.push(self.load_node(child)?);
+                    }
+                }
+            }
+            self.storage.remove(node.hash())?;
+        }
+
+        path_nodes.reverse();
+        side_nodes.reverse();
+        self.delete_with_path_set(&path_nodes, &side_nodes)?;
+
+        Ok(leaves_count)
+    }
+
+    fn load_node(&self, key: &Bytes32) -> Result<Node, MerkleTreeError<StorageError>> {
+        self.storage
+            .get(key)?
+            .ok_or(MerkleTreeError::LoadError(*key))?
+            .into_owned()
+            .try_into()
+            .map_err(MerkleTreeError::DeserializeError)
+    }
+
     fn update_with_path_set(
         &mut self,
         requested_leaf_node: &Node,
@@ -591,7 +1026,7 @@ where
                 // start merging the remaining side nodes.
                 // See https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.find.
                 if let Some(side_node) = side_nodes_iter
-                    .find(|side_node| *side_node != Node::Placeholder.hash())
+                    .find(|side_node| *side_node != zero_sum())
                 {
                     // Skip parents until the parent of the first side node is found
                     if let Some(old_parent) = path_nodes_iter.find(|parent| {
@@ -645,17 +1080,40 @@ where
     }
 }
 
-impl<TableType, StorageType, StorageError> MerkleTree<TableType, StorageType>
+impl<TableType, StorageType, StorageError, Key> MerkleTree<TableType, StorageType, Key>
 where
     TableType: Mappable<Key = Bytes32, Value = Primitive, OwnedValue = Primitive>,
     StorageType: StorageInspect<TableType, Error = StorageError>,
+    Key: Borrow<Bytes32>,
 {
+    #[must_use = "calling generate_proof() without using the result is a likely bug"]
     pub fn generate_proof(
         &self,
-        key: &MerkleTreeKey,
+        key: &Key,
+    ) -> Result<Proof, MerkleTreeError<StorageError>> {
+        self.generate_proof_from(key, None)
+    }
+
+    /// Generate a proof for `key` like [`generate_proof`](Self::generate_proof),
+    /// looking up the nodes on its path in `cache` before loading them from
+    /// storage. Nodes loaded from storage are added to the cache.
+    #[must_use = "calling generate_proof_with_cache() without using the result is a likely bug"]
+    pub fn generate_proof_with_cache(
+        &self,
+        key: &Key,
+        cache: &mut NodeCache,
+    ) -> Result<Proof, MerkleTreeError<StorageError>> {
+        self.generate_proof_from(key, Some(cache))
+    }
+
+    fn generate_proof_from(
+        &self,
+        key: &Key,
+        cache: Option<&mut NodeCache>,
     ) -> Result<Proof, MerkleTreeError<StorageError>> {
-        let path = key.as_ref();
-        let (path_nodes, side_nodes) = self.path_set(path)?;
+        let path = &self.truncate_key(key.borrow());
+        let (path_nodes, side_nodes) =
+            self.limited_path_set(path, self.max_proof_depth, cache)?;
         // Identify the closest leaf that is included in the tree to the
         // requested leaf. The closest leaf, as returned by the path set
         // corresponding to the requested leaf, will be the requested leaf
@@ -711,6 +1169,345 @@ where
         };
         Ok(proof)
     }
+
+    /// Generates the path from the leaf of `key` up to the root, with the
+    /// position, hash and side of each sibling on it.
+    ///
+    /// The steps are ordered like the side nodes of the proof returned by
+    /// [`generate_proof`](Self::generate_proof), from the leaf upwards.
+    pub fn path(
+        &self,
+        key: &Key,
+    ) -> Result<Vec<PathStep>, MerkleTreeError<StorageError>> {
+        let proof = self.generate_proof(key)?;
+        // SAFETY: The key is only used to locate the side nodes of the proof
+        // and is not handed out.
+        let key = unsafe { MerkleTreeKey::convert(self.truncate_key(key.borrow())) };
+        let steps = proof
+            .with_positions(&key)
+            .map(|(position, sibling_hash)| {
+                #[allow(clippy::arithmetic_side_effects)] // The depth is in 1..=256
+                let index = position.depth() - 1;
+                let direction = key.get_instruction(index).expect("Infallible").into();
+                PathStep {
+                    position,
+                    sibling_hash,
+                    direction,
+                }
+            })
+            .collect();
+        Ok(steps)
+    }
+
+    /// Generates the part of the proof for `key` that leads from the leaf up
+    /// to its ancestor at `ancestor_depth`, where depth 0 is the root. See
+    /// [`SubpathProof`].
+    #[must_use = "calling generate_subpath_proof() without using the result is a likely bug"]
+    pub fn generate_subpath_proof(
+        &self,
+        key: &Key,
+        ancestor_depth: u8,
+    ) -> Result<SubpathProof, MerkleTreeError<StorageError>> {
+        let proof = self.generate_proof(key)?;
+        Ok(SubpathProof::new(proof, ancestor_depth))
+    }
+
+    /// Generate the inclusion proof of every leaf in the tree, in ascending key
+    /// order.
+    ///
+    /// Like [`generate_all_proofs`](Self::generate_all_proofs), every node is
+    /// read from the storage once during a single depth-first traversal, but
+    /// any storage is supported. A node missing from the storage is reported
+    /// as [`MerkleTreeError::LoadError`], and a leaf deeper than the limit set
+    /// with [`with_max_proof_depth`](Self::with_max_proof_depth) as
+    /// [`ProofError::DepthExceeded`].
+    pub fn generate_all_proofs_dfs(
+        &self,
+    ) -> Result<Vec<(MerkleTreeKey, Proof)>, MerkleTreeError<StorageError>> {
+        all_proofs(self.root_node(), self.max_proof_depth, |key| {
+            self.storage
+                .get(key)?
+                .ok_or(MerkleTreeError::LoadError(*key))?
+                .into_owned()
+                .try_into()
+                .map_err(MerkleTreeError::DeserializeError)
+        })
+    }
+}
+
+impl<TableType, StorageType, Key> MerkleTree<TableType, StorageType, Key>
+where
+    TableType: Mappable<
+            Key = Bytes32,
+            OwnedKey = Bytes32,
+            Value = Primitive,
+            OwnedValue = Primitive,
+        >,
+    StorageType: BorrowMut<StorageMap<TableType>>,
+{
+    /// Remove all nodes from the storage that are not reachable from the
+    /// current root, returning the number of removed nodes.
+    ///
+    /// Nodes are reachable if they can be found by descending from the root
+    /// node through the children of internal nodes. Any other entry in the
+    /// storage, such as an intermediate node left behind by a sequence of
+    /// inserts and deletes, is considered orphaned and is removed.
+    pub fn compact(&mut self) -> usize {
+        let mut reachable = HashSet::<Bytes32>::new();
+        let mut pending = Vec::new();
+        if !self.root_node().is_placeholder() {
+            pending.push(*self.root_node().hash());
+        }
+
+        let storage = self.storage.borrow_mut();
+        while let Some(key) = pending.pop() {
+            if !reachable.insert(key) {
+                continue
+            }
+            let Some(node) = StorageInspectInfallible::get(&*storage, &key)
+                .and_then(|primitive| Node::try_from(primitive.into_owned()).ok())
+            else {
+                continue
+            };
+            if node.is_node() {
+                for child in [node.left_child_key(), node.right_child_key()] {
+                    if child != zero_sum() {
+                        pending.push(*child);
+                    }
+                }
+            }
+        }
+
+        let len_before = storage.len();
+        storage.retain(|key, _| reachable.contains(key));
+        #[allow(clippy::arithmetic_side_effects)] // retain never adds entries
+        let removed = len_before - storage.len();
+        removed
+    }
+}
+
+impl<TableType, StorageType, Key> MerkleTree<TableType, StorageType, Key>
+where
+    TableType: Mappable<
+            Key = Bytes32,
+            OwnedKey = Bytes32,
+            Value = Primitive,
+            OwnedValue = Primitive,
+        >,
+    StorageType: Borrow<StorageMap<TableType>>,
+{
+    /// Iterate over the leaves of the tree, yielding the key and the leaf hash
+    /// of each leaf.
+    ///
+    /// Leaves are visited with a depth-first traversal from the root, and are
+    /// therefore yielded in ascending key order. A node missing from the
+    /// storage is reported as [`MerkleTreeError::LoadError`], after which the
+    /// iterator ends.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = Result<(MerkleTreeKey, Bytes32), MerkleTreeError<Infallible>>> + '_
+    {
+        self.leaves_below(Ok(self.root_node().clone()))
+    }
+
+    /// Iterate over the keys of the leaves whose key starts with the first
+    /// `prefix_bits` bits of `prefix_bytes`, in ascending key order.
+    ///
+    /// Only the subtree rooted at the end of the prefix bit-path is traversed,
+    /// so the cost depends on the number of matching leaves rather than on the
+    /// size of the tree. Bits past the end of `prefix_bytes` are ignored. A
+    /// node missing from the storage is reported as
+    /// [`MerkleTreeError::LoadError`], after which the iterator ends.
+    pub fn keys_with_prefix(
+        &self,
+        prefix_bytes: &[u8],
+        prefix_bits: u8,
+    ) -> impl Iterator<Item = Result<MerkleTreeKey, MerkleTreeError<Infallible>>> + '_
+    {
+        let subtree = self.prefix_subtree(prefix_bytes, prefix_bits);
+        self.leaves_below(subtree)
+            .map(|leaf| leaf.map(|(key, _)| key))
+    }
+
+    /// The root of the subtree holding the leaves whose key starts with the
+    /// first `prefix_bits` bits of `prefix_bytes`, or a placeholder if there
+    /// are none.
+    fn prefix_subtree(
+        &self,
+        prefix_bytes: &[u8],
+        prefix_bits: u8,
+    ) -> Result<Node, MerkleTreeError<Infallible>> {
+        let storage: &StorageMap<TableType> = self.storage.borrow();
+        let prefix_bits =
+            usize::from(prefix_bits).min(prefix_bytes.len().saturating_mul(8));
+
+        let mut subtree = self.root_node().clone();
+        for depth in 0..prefix_bits {
+            if subtree.is_placeholder() {
+                break
+            }
+            if subtree.is_leaf() {
+                // A leaf placed above the end of the prefix bit-path is the only
+                // leaf of its subtree, and may or may not share the prefix.
+                let common = subtree.leaf_key().common_prefix_count(prefix_bytes);
+                if common < prefix_bits as u64 {
+                    subtree = Node::create_placeholder();
+                }
+                break
+            }
+            // `depth` is below `prefix_bits`, which is clamped to the length of
+            // `prefix_bytes` in bits
+            #[allow(clippy::arithmetic_side_effects)]
+            let goes_right = prefix_bytes[depth / 8] & (0x80 >> (depth % 8)) != 0;
+            let child = if goes_right {
+                subtree.right_child_key()
+            } else {
+                subtree.left_child_key()
+            };
+            subtree = if child == zero_sum() {
+                Node::create_placeholder()
+            } else {
+                load_stored_node(storage, child)?
+            };
+        }
+        Ok(subtree)
+    }
+
+    /// Iterate over the leaves of the subtree rooted at `subtree`, in
+    /// ascending key order. The iterator ends after the first error.
+    fn leaves_below(
+        &self,
+        subtree: Result<Node, MerkleTreeError<Infallible>>,
+    ) -> impl Iterator<Item = Result<(MerkleTreeKey, Bytes32), MerkleTreeError<Infallible>>> + '_
+    {
+        let storage: &StorageMap<TableType> = self.storage.borrow();
+        let mut pending = Vec::new();
+        if subtree.as_ref().map_or(true, |node| !node.is_placeholder()) {
+            pending.push(subtree);
+        }
+
+        iter::from_fn(move || {
+            while let Some(node) = pending.pop() {
+                let node = match node {
+                    Ok(node) => node,
+                    Err(err) => {
+                        pending.clear();
+                        return Some(Err(err))
+                    }
+                };
+                if node.is_leaf() {
+                    // SAFETY: The key is the path of a leaf already present in
+                    // the tree, so it cannot be used to manipulate its structure.
+                    let key = unsafe { MerkleTreeKey::convert(*node.leaf_key()) };
+                    return Some(Ok((key, *node.hash())))
+                }
+                // Push the right child first so that the left subtree is
+                // visited first.
+                for child in [node.right_child_key(), node.left_child_key()] {
+                    if child != zero_sum() {
+                        pending.push(load_stored_node(storage, child));
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    /// Generate the inclusion proof of every leaf in the tree, in ascending key
+    /// order.
+    ///
+    /// All proofs are built during a single depth-first traversal that keeps
+    /// the side nodes of the current path on a stack, so every node is read
+    /// from the storage once, instead of once per leaf below it as with
+    /// repeated calls to [`generate_proof`](Self::generate_proof). A node
+    /// missing from the storage is reported as [`MerkleTreeError::LoadError`],
+    /// and a leaf deeper than the limit set with
+    /// [`with_max_proof_depth`](Self::with_max_proof_depth) as
+    /// [`ProofError::DepthExceeded`].
+    pub fn generate_all_proofs(
+        &self,
+    ) -> Result<Vec<(MerkleTreeKey, Proof)>, MerkleTreeError<Infallible>> {
+        let storage: &StorageMap<TableType> = self.storage.borrow();
+        all_proofs(self.root_node(), self.max_proof_depth, |key| {
+            load_stored_node(storage, key)
+        })
+    }
+}
+
+/// Load the node stored under `key` in an in-memory storage.
+fn load_stored_node<TableType>(
+    storage: &StorageMap<TableType>,
+    key: &Bytes32,
+) -> Result<Node, MerkleTreeError<Infallible>>
+where
+    TableType: Mappable<
+            Key = Bytes32,
+            OwnedKey = Bytes32,
+            Value = Primitive,
+            OwnedValue = Primitive,
+        >,
+{
+    StorageInspectInfallible::get(storage, key)
+        .ok_or(MerkleTreeError::LoadError(*key))?
+        .into_owned()
+        .try_into()
+        .map_err(MerkleTreeError::DeserializeError)
+}
+
+/// Build the inclusion proof of every leaf below `root`, in ascending key
+/// order, with a single depth-first traversal.
+///
+/// The side nodes of the current path are kept on a stack, so every node is
+/// loaded once with `load`. The traversal stops with
+/// [`ProofError::DepthExceeded`] as soon as the path holds more than
+/// `max_depth` side nodes.
+fn all_proofs<E>(
+    root: &Node,
+    max_depth: Option<u8>,
+    mut load: impl FnMut(&Bytes32) -> Result<Node, MerkleTreeError<E>>,
+) -> Result<Vec<(MerkleTreeKey, Proof)>, MerkleTreeError<E>> {
+    let mut proofs = Vec::new();
+    // Side nodes of the current path, ordered from the root downwards
+    let mut side_nodes = Vec::<Bytes32>::new();
+    // Nodes to visit, with the depth of their parent's side nodes and their own
+    // side node
+    let mut pending = Vec::new();
+    if !root.is_placeholder() {
+        pending.push((root.clone(), 0, None));
+    }
+
+    while let Some((node, depth, side_node)) = pending.pop() {
+        side_nodes.truncate(depth);
+        side_nodes.extend(side_node);
+        if let Some(max_depth) =
+            max_depth.filter(|max_depth| side_nodes.len() > usize::from(*max_depth))
+        {
+            let err = ProofError::DepthExceeded { max_depth };
+            return Err(MerkleTreeError::ProofError(err))
+        }
+
+        if node.is_leaf() {
+            // SAFETY: The key is the path of a leaf already present in the
+            // tree, so it cannot be used to manipulate its structure.
+            let key = unsafe { MerkleTreeKey::convert(*node.leaf_key()) };
+            let proof_set = side_nodes.iter().rev().copied().collect();
+            proofs.push((key, Proof::Inclusion(InclusionProof { proof_set })));
+            continue
+        }
+
+        let left = *node.left_child_key();
+        let right = *node.right_child_key();
+        // Push the right child first so that the left subtree is visited first.
+        for (child, sibling) in [(right, left), (left, right)] {
+            if child == *zero_sum() {
+                continue
+            }
+            let child = load(&child)?;
+            pending.push((child, side_nodes.len(), Some(sibling)));
+        }
+    }
+
+    Ok(proofs)
 }
 
 #[cfg(test)]
@@ -727,12 +1524,22 @@ mod test {
             MerkleTree,
             MerkleTreeError,
             MerkleTreeKey,
+            NodeCache,
             Primitive,
             empty_sum,
+            proof::{
+                Proof,
+                ProofError,
+            },
         },
     };
+    use alloc::vec::Vec;
     use fuel_storage::Mappable;
     use hex;
+    use rand::{
+        SeedableRng,
+        prelude::StdRng,
+    };
 
     fn random_bytes32<R>(rng: &mut R) -> Bytes32
     where
@@ -757,6 +1564,133 @@ mod test {
         MerkleTreeKey::new(data.as_ref())
     }
 
+    #[test]
+    fn merkle_tree_key_accessors_agree() {
+        let key = key(b"\x00\x00\x00\x00");
+        let slice: &[u8] = key.as_ref();
+
+        assert_eq!(key.as_bytes(), &*key);
+        assert_eq!(key.as_bytes().as_slice(), slice);
+        assert_eq!(key.into_bytes(), Bytes32::from(key));
+    }
+
+    #[test]
+    fn generate_proof_fails_for_leaves_deeper_than_the_max_proof_depth() {
+        // The first two keys only differ in their last bit, so their leaves
+        // are at depth 256
+        let mut last_bit_set = [0u8; 32];
+        last_bit_set[31] = 0x01;
+        let deep_key = MerkleTreeKey::new_without_hash([0u8; 32]);
+        let deep_sibling_key = MerkleTreeKey::new_without_hash(last_bit_set);
+        let shallow_key = MerkleTreeKey::new_without_hash([0xff; 32]);
+
+        let mut storage = StorageMap::<TestTable>::new();
+        let root = {
+            let mut tree = MerkleTree::new(&mut storage).with_max_proof_depth(8);
+            assert_eq!(tree.max_proof_depth(), Some(8));
+            for key in [deep_key, deep_sibling_key, shallow_key] {
+                tree.insert(key, b"DATA").unwrap();
+            }
+
+            assert_eq!(tree.generate_proof(&shallow_key).unwrap().depth(), 1);
+            assert!(matches!(
+                tree.generate_proof(&deep_key),
+                Err(MerkleTreeError::ProofError(ProofError::DepthExceeded {
+                    max_depth: 8,
+                }))
+            ));
+            tree.root()
+        };
+
+        // The limit doesn't change the tree, only the proofs it hands out
+        let tree = MerkleTree::load(&mut storage, &root).unwrap();
+        assert_eq!(tree.max_proof_depth(), None);
+        assert_eq!(tree.generate_proof(&deep_key).unwrap().depth(), 256);
+    }
+
+    /// Keys of two leaves at depth 256, which only differ in their last bit,
+    /// and of a leaf at depth 1.
+    fn deep_and_shallow_keys() -> [MerkleTreeKey; 3] {
+        let mut last_bit_set = [0u8; 32];
+        last_bit_set[31] = 0x01;
+        [
+            MerkleTreeKey::new_without_hash([0u8; 32]),
+            MerkleTreeKey::new_without_hash(last_bit_set),
+            MerkleTreeKey::new_without_hash([0xff; 32]),
+        ]
+    }
+
+    #[test]
+    fn update_and_prove_fails_for_leaves_deeper_than_the_max_proof_depth() {
+        let [deep_key, deep_sibling_key, shallow_key] = deep_and_shallow_keys();
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage).with_max_proof_depth(8);
+
+        tree.update_and_prove(deep_key, b"DATA").unwrap();
+        let proof = tree.update_and_prove(shallow_key, b"DATA").unwrap();
+        assert_eq!(proof.depth(), 1);
+
+        let root = tree.root();
+        assert!(matches!(
+            tree.update_and_prove(deep_sibling_key, b"DATA"),
+            Err(MerkleTreeError::ProofError(ProofError::DepthExceeded {
+                max_depth: 8,
+            }))
+        ));
+        // The failed update leaves the tree unchanged
+        assert_eq!(tree.root(), root);
+    }
+
+    #[test]
+    fn generate_all_proofs_fails_for_leaves_deeper_than_the_max_proof_depth() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage).with_max_proof_depth(8);
+        for key in deep_and_shallow_keys() {
+            tree.insert(key, b"DATA").unwrap();
+        }
+
+        assert!(matches!(
+            tree.generate_all_proofs(),
+            Err(MerkleTreeError::ProofError(ProofError::DepthExceeded {
+                max_depth: 8,
+            }))
+        ));
+    }
+
+    #[test]
+    fn generate_all_proofs_dfs_fails_for_leaves_deeper_than_the_max_proof_depth() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage).with_max_proof_depth(8);
+        for key in deep_and_shallow_keys() {
+            tree.insert(key, b"DATA").unwrap();
+        }
+
+        assert!(matches!(
+            tree.generate_all_proofs_dfs(),
+            Err(MerkleTreeError::ProofError(ProofError::DepthExceeded {
+                max_depth: 8,
+            }))
+        ));
+    }
+
+    #[test]
+    fn generate_subpath_proof_fails_for_leaves_deeper_than_the_max_proof_depth() {
+        let [deep_key, _, shallow_key] = deep_and_shallow_keys();
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage).with_max_proof_depth(8);
+        for key in deep_and_shallow_keys() {
+            tree.insert(key, b"DATA").unwrap();
+        }
+
+        tree.generate_subpath_proof(&shallow_key, 0).unwrap();
+        assert!(matches!(
+            tree.generate_subpath_proof(&deep_key, 250),
+            Err(MerkleTreeError::ProofError(ProofError::DepthExceeded {
+                max_depth: 8,
+            }))
+        ));
+    }
+
     #[test]
     fn test_empty_root() {
         let mut storage = StorageMap::<TestTable>::new();
@@ -765,6 +1699,324 @@ mod test {
         let expected_root =
             "0000000000000000000000000000000000000000000000000000000000000000";
         assert_eq!(hex::encode(root), expected_root);
+        assert_eq!(root, crate::sparse::EMPTY_SPARSE_ROOT);
+    }
+
+    #[test]
+    fn tree_with_bytes32_keys_matches_tree_with_merkle_tree_keys() {
+        let keys = [key(b"\x00\x00\x00\x00"), key(b"\x00\x00\x00\x01")];
+
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        for k in keys {
+            tree.insert(k, b"DATA").unwrap();
+        }
+        tree.delete(keys[0]).unwrap();
+
+        let mut bytes_storage = StorageMap::<TestTable>::new();
+        let mut bytes_tree =
+            MerkleTree::new(&mut bytes_storage).into_key_type::<Bytes32>();
+        for k in keys {
+            bytes_tree.insert(k.into_bytes(), b"DATA").unwrap();
+        }
+        bytes_tree.delete(keys[0].into_bytes()).unwrap();
+
+        assert_eq!(tree.root(), bytes_tree.root());
+        assert_eq!(
+            tree.generate_proof(&keys[1]).unwrap(),
+            bytes_tree.generate_proof(keys[1].as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn iter_yields_every_leaf_in_key_order() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        assert_eq!(tree.iter().count(), 0);
+
+        let keys = (0u32..10).map(|i| key(i.to_be_bytes())).collect::<Vec<_>>();
+        for k in keys.iter() {
+            tree.insert(*k, b"DATA").unwrap();
+        }
+        tree.delete(keys[3]).unwrap();
+
+        let mut expected = keys
+            .iter()
+            .filter(|k| **k != keys[3])
+            .map(|k| (*k, *Node::create_leaf(k, b"DATA").hash()))
+            .collect::<Vec<_>>();
+        expected.sort_by_key(|(k, _)| k.into_bytes());
+
+        assert_eq!(
+            tree.iter().collect::<Result<Vec<_>, _>>().unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn iter_yields_root_leaf_of_single_leaf_tree() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        tree.insert(key(b"\x00\x00\x00\x00"), b"DATA").unwrap();
+
+        assert_eq!(
+            tree.iter().collect::<Result<Vec<_>, _>>().unwrap(),
+            [(key(b"\x00\x00\x00\x00"), tree.root())]
+        );
+    }
+
+    #[test]
+    fn keys_with_prefix_yields_exactly_the_keys_sharing_the_prefix() {
+        let rng = &mut StdRng::seed_from_u64(0xDEADBEEF);
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        let mut expected = Vec::new();
+        for _ in 0..100 {
+            let mut bytes = random_bytes32(rng);
+            bytes[0] = 0xab;
+            let key = MerkleTreeKey::new_without_hash(bytes);
+            tree.insert(key, b"DATA").unwrap();
+            expected.push(key);
+        }
+        for _ in 0..100 {
+            let mut bytes = random_bytes32(rng);
+            if bytes[0] == 0xab {
+                bytes[0] = 0xac;
+            }
+            tree.insert(MerkleTreeKey::new_without_hash(bytes), b"DATA")
+                .unwrap();
+        }
+        expected.sort_by_key(|k| k.into_bytes());
+        expected.dedup();
+
+        let keys = tree
+            .keys_with_prefix(&[0xab], 8)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(keys, expected);
+        assert_eq!(
+            tree.keys_with_prefix(&[0xab], 0).count(),
+            tree.iter().count()
+        );
+    }
+
+    #[test]
+    fn bulk_delete_prefix_removes_exactly_the_keys_sharing_the_prefix() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        let mut expected_storage = StorageMap::<TestTable>::new();
+        let mut expected = MerkleTree::new(&mut expected_storage);
+
+        let mut deleted = 0;
+        for i in 0u32..200 {
+            let k = key(i.to_be_bytes());
+            tree.insert(k, b"DATA").unwrap();
+            if k.as_bytes()[0] & 0xf0 == 0xa0 {
+                deleted += 1;
+            } else {
+                expected.insert(k, b"DATA").unwrap();
+            }
+        }
+
+        assert_eq!(tree.bulk_delete_prefix(&[0xa0], 4).unwrap(), deleted);
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.keys_with_prefix(&[0xa0], 4).count(), 0);
+        assert_eq!(tree.bulk_delete_prefix(&[0xa0], 4).unwrap(), 0);
+
+        assert_eq!(tree.bulk_delete_prefix(&[], 0).unwrap(), 200 - deleted);
+        assert_eq!(tree.root(), *empty_sum());
+    }
+
+    #[test]
+    fn update_and_prove_returns_the_proof_of_the_updated_leaf() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        for i in 0u32..10 {
+            let k = key(i.to_be_bytes());
+            let proof = tree.update_and_prove(k, b"DATA").unwrap();
+            assert_eq!(proof, tree.generate_proof(&k).unwrap());
+
+            let Proof::Inclusion(proof) = proof else {
+                panic!("Expected an inclusion proof")
+            };
+            assert!(proof.verify(&tree.root(), &k, b"DATA"));
+        }
+
+        // Updating an existing leaf
+        let k = key(3u32.to_be_bytes());
+        let proof = tree.update_and_prove(k, b"CHANGE").unwrap();
+        assert_eq!(proof, tree.generate_proof(&k).unwrap());
+        let Proof::Inclusion(proof) = proof else {
+            panic!("Expected an inclusion proof")
+        };
+        assert!(proof.verify(&tree.root(), &k, b"CHANGE"));
+    }
+
+    #[test]
+    fn generate_all_proofs_matches_generate_proof_for_every_leaf() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        assert!(tree.generate_all_proofs().unwrap().is_empty());
+
+        tree.insert(key(0u32.to_be_bytes()), b"DATA").unwrap();
+        let proofs = tree.generate_all_proofs().unwrap();
+        assert_eq!(proofs.len(), 1);
+        assert_eq!(proofs[0].1, tree.generate_proof(&proofs[0].0).unwrap());
+
+        for i in 1u32..20 {
+            tree.insert(key(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        tree.delete(key(7u32.to_be_bytes())).unwrap();
+
+        let proofs = tree.generate_all_proofs().unwrap();
+        assert_eq!(proofs.len(), 19);
+        for (k, proof) in proofs {
+            assert!(proof.is_inclusion());
+            assert_eq!(proof, tree.generate_proof(&k).unwrap());
+        }
+    }
+
+    #[test]
+    fn generate_all_proofs_dfs_matches_generate_all_proofs() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        assert!(tree.generate_all_proofs_dfs().unwrap().is_empty());
+
+        for i in 0u32..20 {
+            tree.insert(key(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        tree.delete(key(7u32.to_be_bytes())).unwrap();
+
+        assert_eq!(
+            tree.generate_all_proofs_dfs().unwrap(),
+            tree.generate_all_proofs().unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_all_proofs_dfs_returns_load_error_for_a_missing_node() {
+        use fuel_storage::{
+            StorageInspect,
+            StorageMutate,
+        };
+
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        for i in 0u32..10 {
+            tree.insert(key(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        let root = tree.root();
+
+        let (_, _, left, right) = storage.get(&root).unwrap().unwrap().into_owned();
+        let missing = [left, right]
+            .into_iter()
+            .find(|child| child != empty_sum())
+            .unwrap();
+        storage.remove(&missing).unwrap();
+
+        let tree = MerkleTree::load(&mut storage, &root).unwrap();
+        assert!(matches!(
+            tree.generate_all_proofs_dfs(),
+            Err(MerkleTreeError::LoadError(key)) if key == missing
+        ));
+    }
+
+    #[test]
+    fn missing_node_is_reported_by_iter_keys_with_prefix_and_generate_all_proofs() {
+        use fuel_storage::{
+            StorageInspect,
+            StorageMutate,
+        };
+
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        for i in 0u32..10 {
+            tree.insert(key(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        let root = tree.root();
+
+        let (_, _, left, right) = storage.get(&root).unwrap().unwrap().into_owned();
+        let missing = [left, right]
+            .into_iter()
+            .find(|child| child != empty_sum())
+            .unwrap();
+        storage.remove(&missing).unwrap();
+
+        let tree = MerkleTree::load(&mut storage, &root).unwrap();
+        let errors = tree.iter().filter(Result::is_err).count();
+        assert_eq!(errors, 1);
+        assert!(matches!(
+            tree.iter().find_map(Result::err),
+            Some(MerkleTreeError::LoadError(key)) if key == missing
+        ));
+        assert!(matches!(
+            tree.keys_with_prefix(&[], 0).find_map(Result::err),
+            Some(MerkleTreeError::LoadError(key)) if key == missing
+        ));
+        assert!(matches!(
+            tree.generate_all_proofs(),
+            Err(MerkleTreeError::LoadError(key)) if key == missing
+        ));
+    }
+
+    #[test]
+    fn generate_proof_with_cache_matches_generate_proof() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        let keys = (0u32..20).map(|i| key(i.to_be_bytes())).collect::<Vec<_>>();
+        for k in keys.iter() {
+            tree.insert(*k, b"DATA").unwrap();
+        }
+
+        // Warm the cache, then modify the tree
+        let mut cache = NodeCache::new(1024);
+        for k in keys.iter() {
+            tree.generate_proof_with_cache(k, &mut cache).unwrap();
+        }
+        assert!(!cache.is_empty());
+        tree.insert(keys[3], b"CHANGE").unwrap();
+        tree.delete(keys[7]).unwrap();
+
+        let cached = keys
+            .iter()
+            .map(|k| tree.generate_proof_with_cache(k, &mut cache).unwrap())
+            .collect::<Vec<_>>();
+        let uncached = keys
+            .iter()
+            .map(|k| tree.generate_proof(k).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(cached, uncached);
+    }
+
+    #[test]
+    fn node_cache_holds_at_most_its_capacity() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        let keys = (0u32..64).map(|i| key(i.to_be_bytes())).collect::<Vec<_>>();
+        for k in keys.iter() {
+            tree.insert(*k, b"DATA").unwrap();
+        }
+
+        let mut cache = NodeCache::new(8);
+        for k in keys.iter() {
+            let proof = tree.generate_proof_with_cache(k, &mut cache).unwrap();
+            assert_eq!(proof, tree.generate_proof(k).unwrap());
+            assert!(cache.len() <= cache.capacity());
+        }
+        assert_eq!(cache.len(), 8);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn merkle_tree_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<MerkleTree<TestTable, StorageMap<TestTable>>>();
+        assert_send_sync::<crate::sparse::in_memory::MerkleTree>();
     }
 
     #[test]
@@ -1167,6 +2419,73 @@ mod test {
         assert_eq!(tree.storage().len(), size_before_tenth);
     }
 
+    #[test]
+    fn test_compact_removes_unreachable_nodes() {
+        let mut storage = StorageMap::<TestTable>::new();
+
+        // Given
+        // - The storage holds the nodes of a previously built, unrelated tree
+        let stale_set = (100_u32..110).map(|i| (key(i.to_be_bytes()), b"STALE"));
+        let _ = MerkleTree::from_set(&mut storage, stale_set).unwrap();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        // - The tree is built with alternating inserts and deletes
+        for i in 0_u32..100 {
+            tree.insert(key(i.to_be_bytes()), b"DATA").unwrap();
+            if i % 2 == 1 {
+                tree.delete(key((i - 1).to_be_bytes())).unwrap();
+            }
+        }
+        for i in (1_u32..100).step_by(4) {
+            tree.insert(key(i.to_be_bytes()), b"CHANGED").unwrap();
+        }
+        let root = tree.root();
+        let len_before = tree.storage().len();
+
+        // When
+        let removed = tree.compact();
+
+        // Then
+        let expected_storage = {
+            let mut storage = StorageMap::<TestTable>::new();
+            let set = (0_u32..100).filter(|i| i % 2 == 1).map(|i| {
+                let data: &[u8] = if i % 4 == 1 { b"CHANGED" } else { b"DATA" };
+                (key(i.to_be_bytes()), data)
+            });
+            let expected_tree = MerkleTree::from_set(&mut storage, set).unwrap();
+            assert_eq!(expected_tree.root(), root);
+            storage
+        };
+        assert_eq!(tree.root(), root);
+        assert_eq!(tree.storage().len(), expected_storage.len());
+        assert_eq!(removed, len_before - tree.storage().len());
+        assert_ne!(removed, 0);
+        assert_eq!(tree.compact(), 0);
+    }
+
+    #[test]
+    fn test_compact_on_empty_tree_removes_all_nodes() {
+        use fuel_storage::StorageMutate;
+
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        // Given
+        tree.insert(key(b"\x00\x00\x00\x00"), b"DATA").unwrap();
+        tree.insert(key(b"\x00\x00\x00\x01"), b"DATA").unwrap();
+        tree.delete(key(b"\x00\x00\x00\x00")).unwrap();
+        tree.delete(key(b"\x00\x00\x00\x01")).unwrap();
+        let stale_node = (0, 0, [1; 32], [2; 32]);
+        tree.storage.insert(&[3; 32], &stale_node).unwrap();
+
+        // When
+        tree.compact();
+
+        // Then
+        assert_eq!(tree.root(), *MerkleTree::<TestTable, ()>::empty_root());
+        assert!(tree.storage().is_empty());
+    }
+
     #[test]
     fn test_delete_sparse_union() {
         let mut storage = StorageMap::<TestTable>::new();
@@ -1307,6 +2626,36 @@ mod test {
         assert!(matches!(err, MerkleTreeError::LoadError(_)));
     }
 
+    #[test]
+    fn verify_root_detects_a_corrupted_node() {
+        use fuel_storage::{
+            StorageInspect,
+            StorageMutate,
+        };
+
+        let mut storage = StorageMap::<TestTable>::new();
+
+        let mut tree = MerkleTree::new(&mut storage);
+        assert!(tree.verify_root().unwrap());
+        for i in 0u32..10 {
+            tree.insert(key(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        assert!(tree.verify_root().unwrap());
+        let root = tree.root();
+
+        // Flip one bit in the content of a child of the root.
+        let (_, _, left, right) = storage.get(&root).unwrap().unwrap().into_owned();
+        let (node_key, mut primitive) = [left, right]
+            .into_iter()
+            .find_map(|key| Some((key, storage.get(&key).unwrap()?.into_owned())))
+            .unwrap();
+        primitive.2[0] ^= 1;
+        storage.insert(&node_key, &primitive).unwrap();
+
+        let tree = MerkleTree::load(&mut storage, &root).unwrap();
+        assert!(!tree.verify_root().unwrap());
+    }
+
     #[test]
     fn test_load_returns_a_deserialize_error_if_the_storage_is_corrupted() {
         use fuel_storage::StorageMutate;
@@ -1340,7 +2689,7 @@ mod test {
                 random_bytes32(rng),
             ))
         };
-        let data = std::iter::from_fn(generator)
+        let data = core::iter::from_fn(generator)
             .take(1_000)
             .collect::<Vec<_>>();
 
@@ -1372,7 +2721,7 @@ mod test {
                 random_bytes32(rng),
             ))
         };
-        let data = std::iter::from_fn(generator).take(0).collect::<Vec<_>>();
+        let data = core::iter::from_fn(generator).take(0).collect::<Vec<_>>();
 
         let expected_root = {
             let mut storage = StorageMap::<TestTable>::new();
@@ -1402,7 +2751,7 @@ mod test {
                 random_bytes32(rng),
             ))
         };
-        let data = std::iter::from_fn(generator).take(1).collect::<Vec<_>>();
+        let data = core::iter::from_fn(generator).take(1).collect::<Vec<_>>();
 
         let expected_root = {
             let mut storage = StorageMap::<TestTable>::new();
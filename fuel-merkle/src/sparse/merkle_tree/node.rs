@@ -33,14 +33,24 @@ use crate::{
     },
 };
 
+use super::NodeCache;
 use crate::common::node::ChildKeyResult;
 use core::{
+    cell::RefCell,
     fmt,
     marker::PhantomData,
 };
 
+/// A node of a sparse Merkle tree: a leaf, an internal node, or the
+/// placeholder of an empty subtree.
+///
+/// Nodes are built with [`Node::leaf`], [`Node::internal`] and [`Node::empty`],
+/// or decoded from the [`Primitive`] they are stored as.
 #[derive(Clone, PartialEq, Eq)]
-pub(super) enum Node {
+pub struct Node(NodeKind);
+
+#[derive(Clone, PartialEq, Eq)]
+enum NodeKind {
     Node {
         hash: Bytes32,
         height: u32,
@@ -52,63 +62,97 @@ pub(super) enum Node {
 }
 
 impl Node {
-    pub fn max_height() -> u32 {
+    /// Creates a leaf node for `key` whose data has already been hashed to
+    /// `data_hash`. This matches the leaf the tree stores after inserting
+    /// data `d` with `data_hash == sum(d)`.
+    pub fn leaf(key: &Bytes32, data_hash: &Bytes32) -> Self {
+        Self(NodeKind::Node {
+            hash: calculate_leaf_hash(key, data_hash),
+            height: 0u32,
+            prefix: Prefix::Leaf,
+            bytes_lo: *key,
+            bytes_hi: *data_hash,
+        })
+    }
+
+    /// Creates an internal node at `height` from the hashes of its children.
+    ///
+    /// The height is not part of the node's hash, but it is stored with the
+    /// node, and the tree reads it back to find where the paths of two leaves
+    /// diverge. The node equals the one the tree stores when `height` is the
+    /// number of levels between the node and the bottom of the tree, i.e.
+    /// `256` minus its depth.
+    pub fn internal(left_hash: &Bytes32, right_hash: &Bytes32, height: u32) -> Self {
+        Self::create_node_from_hashes(*left_hash, *right_hash, height)
+    }
+
+    /// Creates the placeholder node representing an empty subtree. Its hash is
+    /// the zero hash.
+    pub fn empty() -> Self {
+        Self::create_placeholder()
+    }
+
+    pub(crate) fn max_height() -> u32 {
         Node::key_size_bits()
     }
 
-    pub fn new(
+    pub(crate) fn new(
         height: u32,
         prefix: Prefix,
         bytes_lo: Bytes32,
         bytes_hi: Bytes32,
     ) -> Self {
-        Self::Node {
+        Self(NodeKind::Node {
             hash: calculate_hash(&prefix, &bytes_lo, &bytes_hi),
             height,
             prefix,
             bytes_lo,
             bytes_hi,
-        }
+        })
     }
 
-    pub fn create_leaf<D: AsRef<[u8]>>(key: &Bytes32, data: D) -> Self {
+    pub(crate) fn create_leaf<D: AsRef<[u8]>>(key: &Bytes32, data: D) -> Self {
         let bytes_hi = sum(data);
-        Self::Node {
+        Self(NodeKind::Node {
             hash: calculate_leaf_hash(key, &bytes_hi),
             height: 0u32,
             prefix: Prefix::Leaf,
             bytes_lo: *key,
             bytes_hi,
-        }
+        })
     }
 
-    pub fn create_node(left_child: &Node, right_child: &Node, height: u32) -> Self {
+    pub(crate) fn create_node(
+        left_child: &Node,
+        right_child: &Node,
+        height: u32,
+    ) -> Self {
         let bytes_lo = *left_child.hash();
         let bytes_hi = *right_child.hash();
-        Self::Node {
+        Self(NodeKind::Node {
             hash: calculate_node_hash(&bytes_lo, &bytes_hi),
             height,
             prefix: Prefix::Node,
             bytes_lo,
             bytes_hi,
-        }
+        })
     }
 
-    pub fn create_node_from_hashes(
+    pub(crate) fn create_node_from_hashes(
         bytes_lo: Bytes32,
         bytes_hi: Bytes32,
         height: u32,
     ) -> Self {
-        Self::Node {
+        Self(NodeKind::Node {
             hash: calculate_node_hash(&bytes_lo, &bytes_hi),
             height,
             prefix: Prefix::Node,
             bytes_lo,
             bytes_hi,
-        }
+        })
     }
 
-    pub fn create_node_on_path(
+    pub(crate) fn create_node_on_path(
         path: &dyn Path,
         path_node: &Node,
         side_node: &Node,
@@ -142,11 +186,11 @@ impl Node {
         }
     }
 
-    pub fn create_placeholder() -> Self {
-        Self::Placeholder
+    pub(crate) fn create_placeholder() -> Self {
+        Self(NodeKind::Placeholder)
     }
 
-    pub fn common_path_length(&self, other: &Node) -> u64 {
+    pub(crate) fn common_path_length(&self, other: &Node) -> u64 {
         debug_assert!(self.is_leaf());
         debug_assert!(other.is_leaf());
 
@@ -162,9 +206,9 @@ impl Node {
     }
 
     pub fn height(&self) -> u32 {
-        match self {
-            Node::Node { height, .. } => *height,
-            Node::Placeholder => 0,
+        match &self.0 {
+            NodeKind::Node { height, .. } => *height,
+            NodeKind::Placeholder => 0,
         }
     }
 
@@ -177,34 +221,35 @@ impl Node {
     }
 
     pub fn is_placeholder(&self) -> bool {
-        &Self::Placeholder == self
+        matches!(self.0, NodeKind::Placeholder)
     }
 
+    /// The hash of this node; the zero hash for a placeholder.
     pub fn hash(&self) -> &Bytes32 {
-        match self {
-            Node::Node { hash, .. } => hash,
-            Node::Placeholder => zero_sum(),
+        match &self.0 {
+            NodeKind::Node { hash, .. } => hash,
+            NodeKind::Placeholder => zero_sum(),
         }
     }
 
     fn prefix(&self) -> Prefix {
-        match self {
-            Node::Node { prefix, .. } => *prefix,
-            Node::Placeholder => Prefix::Leaf,
+        match &self.0 {
+            NodeKind::Node { prefix, .. } => *prefix,
+            NodeKind::Placeholder => Prefix::Leaf,
         }
     }
 
-    pub fn bytes_lo(&self) -> &Bytes32 {
-        match self {
-            Node::Node { bytes_lo, .. } => bytes_lo,
-            Node::Placeholder => zero_sum(),
+    pub(crate) fn bytes_lo(&self) -> &Bytes32 {
+        match &self.0 {
+            NodeKind::Node { bytes_lo, .. } => bytes_lo,
+            NodeKind::Placeholder => zero_sum(),
         }
     }
 
-    pub fn bytes_hi(&self) -> &Bytes32 {
-        match self {
-            Node::Node { bytes_hi, .. } => bytes_hi,
-            Node::Placeholder => zero_sum(),
+    pub(crate) fn bytes_hi(&self) -> &Bytes32 {
+        match &self.0 {
+            NodeKind::Node { bytes_hi, .. } => bytes_hi,
+            NodeKind::Placeholder => zero_sum(),
         }
     }
 
@@ -348,8 +393,12 @@ impl fmt::Debug for Node {
     }
 }
 
+/// A caller's [`NodeCache`], shared by the nodes of a single walk down the tree.
+pub(super) type SharedNodeCache<'a> = RefCell<&'a mut NodeCache>;
+
 pub(super) struct StorageNode<'storage, TableType, StorageType> {
     storage: &'storage StorageType,
+    cache: Option<&'storage SharedNodeCache<'storage>>,
     node: Node,
     phantom_table: PhantomData<TableType>,
 }
@@ -358,6 +407,7 @@ impl<TableType, StorageType> Clone for StorageNode<'_, TableType, StorageType> {
     fn clone(&self) -> Self {
         Self {
             storage: self.storage,
+            cache: self.cache,
             node: self.node.clone(),
             phantom_table: Default::default(),
         }
@@ -365,10 +415,37 @@ impl<TableType, StorageType> Clone for StorageNode<'_, TableType, StorageType> {
 }
 
 impl<'s, TableType, StorageType> StorageNode<'s, TableType, StorageType> {
+    #[cfg(test)]
     pub fn new(storage: &'s StorageType, node: Node) -> Self {
         Self {
             node,
             storage,
+            cache: None,
+            phantom_table: Default::default(),
+        }
+    }
+
+    /// Create a storage node whose descendants are looked up in `cache` before
+    /// being loaded from `storage`. Nodes loaded from storage are added to the
+    /// cache.
+    pub fn with_cache(
+        storage: &'s StorageType,
+        cache: Option<&'s SharedNodeCache<'s>>,
+        node: Node,
+    ) -> Self {
+        Self {
+            node,
+            storage,
+            cache,
+            phantom_table: Default::default(),
+        }
+    }
+
+    fn child(&self, node: Node) -> Self {
+        Self {
+            node,
+            storage: self.storage,
+            cache: self.cache,
             phantom_table: Default::default(),
         }
     }
@@ -384,6 +461,35 @@ impl<TableType, StorageType> StorageNode<'_, TableType, StorageType> {
     }
 }
 
+impl<TableType, StorageType> StorageNode<'_, TableType, StorageType>
+where
+    StorageType: StorageInspect<TableType>,
+    TableType: Mappable<Key = Bytes32, Value = Primitive, OwnedValue = Primitive>,
+{
+    fn load_child(&self, key: &Bytes32) -> ChildResult<Self> {
+        if key == zero_sum() {
+            return Ok(self.child(Node::create_placeholder()))
+        }
+        let cached = self.cache.and_then(|cache| cache.borrow_mut().get(key));
+        if let Some(node) = cached {
+            return Ok(self.child(node))
+        }
+        let primitive = self
+            .storage
+            .get(key)
+            .map_err(StorageNodeError::StorageError)?
+            .ok_or(ChildError::ChildNotFound(*key))?;
+        let node: Node = primitive
+            .into_owned()
+            .try_into()
+            .map_err(StorageNodeError::DeserializeError)?;
+        if let Some(cache) = self.cache {
+            cache.borrow_mut().insert(*key, node.clone());
+        }
+        Ok(self.child(node))
+    }
+}
+
 impl<TableType, StorageType> NodeTrait for StorageNode<'_, TableType, StorageType> {
     type Key = Bytes32;
 
@@ -417,6 +523,19 @@ pub enum StorageNodeError<StorageError> {
     DeserializeError(DeserializeError),
 }
 
+#[cfg(feature = "std")]
+impl<StorageError> std::error::Error for StorageNodeError<StorageError>
+where
+    StorageError: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageNodeError::StorageError(err) => Some(err),
+            StorageNodeError::DeserializeError(err) => Some(err),
+        }
+    }
+}
+
 impl<TableType, StorageType> ParentNodeTrait for StorageNode<'_, TableType, StorageType>
 where
     StorageType: StorageInspect<TableType>,
@@ -433,20 +552,7 @@ where
         if self.is_leaf() {
             return Err(ChildError::NodeIsLeaf)
         }
-        let key = self.node.left_child_key();
-        if key == zero_sum() {
-            return Ok(Self::new(self.storage, Node::create_placeholder()))
-        }
-        let primitive = self
-            .storage
-            .get(key)
-            .map_err(StorageNodeError::StorageError)?
-            .ok_or(ChildError::ChildNotFound(*key))?;
-        Ok(primitive
-            .into_owned()
-            .try_into()
-            .map(|node| Self::new(self.storage, node))
-            .map_err(StorageNodeError::DeserializeError)?)
+        self.load_child(self.node.left_child_key())
     }
 
     fn left_child_key(&self) -> ChildKeyResult<Self> {
@@ -460,20 +566,7 @@ where
         if self.is_leaf() {
             return Err(ChildError::NodeIsLeaf)
         }
-        let key = self.node.right_child_key();
-        if key == zero_sum() {
-            return Ok(Self::new(self.storage, Node::create_placeholder()))
-        }
-        let primitive = self
-            .storage
-            .get(key)
-            .map_err(StorageNodeError::StorageError)?
-            .ok_or(ChildError::ChildNotFound(*key))?;
-        Ok(primitive
-            .into_owned()
-            .try_into()
-            .map(|node| Self::new(self.storage, node))
-            .map_err(StorageNodeError::DeserializeError)?)
+        self.load_child(self.node.right_child_key())
     }
 
     fn right_child_key(&self) -> ChildKeyResult<Self> {
@@ -570,6 +663,22 @@ mod test_node {
         assert_eq!(node.hash(), zero_sum());
     }
 
+    #[test]
+    fn test_public_constructors_match_internal_constructors() {
+        let key = sum(b"LEAF");
+        let leaf = Node::leaf(&key, &sum([1u8; 32]));
+        assert_eq!(leaf, Node::create_leaf(&key, [1u8; 32]));
+        assert_eq!(*leaf.hash(), leaf_hash(&key, &[1u8; 32]));
+
+        let left_child = Node::create_leaf(&sum(b"LEFT CHILD"), [1u8; 32]);
+        let right_child = Node::create_leaf(&sum(b"RIGHT CHILD"), [1u8; 32]);
+        let node = Node::internal(left_child.hash(), right_child.hash(), 1);
+        assert_eq!(node, Node::create_node(&left_child, &right_child, 1));
+
+        assert_eq!(Node::empty(), Node::create_placeholder());
+        assert_eq!(Node::empty().hash(), zero_sum());
+    }
+
     #[test]
     fn test_create_leaf_from_primitive_returns_a_valid_leaf() {
         let primitive = (0, Prefix::Leaf as u8, [0xff; 32], [0xff; 32]);
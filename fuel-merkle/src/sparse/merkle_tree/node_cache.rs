@@ -0,0 +1,87 @@
+use super::Node;
+use crate::common::Bytes32;
+
+use alloc::collections::BTreeMap;
+use hashbrown::HashMap;
+
+/// A bounded cache of sparse tree nodes, indexed by their hash.
+///
+/// The cache is owned by the caller and passed to
+/// [`MerkleTree::generate_proof_with_cache`](super::MerkleTree::generate_proof_with_cache),
+/// so repeated proofs over an unchanged part of the tree don't reload the same
+/// nodes from storage. It holds at most `capacity` nodes; once full, the least
+/// recently used node is dropped to make room for a new one.
+///
+/// Nodes are addressed by their hash, so a cached node never goes stale:
+/// updating the tree creates new nodes rather than changing existing ones. The
+/// same cache can be reused across updates to the tree.
+#[derive(Debug, Clone)]
+pub struct NodeCache {
+    capacity: usize,
+    nodes: HashMap<Bytes32, (Node, u64)>,
+    recency: BTreeMap<u64, Bytes32>,
+    clock: u64,
+}
+
+impl NodeCache {
+    /// Create an empty cache holding at most `capacity` nodes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            nodes: HashMap::new(),
+            recency: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// The maximum number of nodes held by the cache.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of nodes currently held by the cache.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Drop all the cached nodes.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.recency.clear();
+    }
+
+    pub(super) fn get(&mut self, key: &Bytes32) -> Option<Node> {
+        let tick = self.tick();
+        let (node, last_used) = self.nodes.get_mut(key)?;
+        self.recency.remove(last_used);
+        self.recency.insert(tick, *key);
+        *last_used = tick;
+        Some(node.clone())
+    }
+
+    pub(super) fn insert(&mut self, key: Bytes32, node: Node) {
+        if self.capacity == 0 {
+            return
+        }
+        let tick = self.tick();
+        if let Some((_, last_used)) = self.nodes.insert(key, (node, tick)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(tick, key);
+        while self.nodes.len() > self.capacity {
+            let Some((_, lru)) = self.recency.pop_first() else {
+                break
+            };
+            self.nodes.remove(&lru);
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock = self.clock.wrapping_add(1);
+        self.clock
+    }
+}
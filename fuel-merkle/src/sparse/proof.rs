@@ -1,6 +1,7 @@
 use crate::{
     common::{
         Bytes32,
+        Msb,
         ProofSet,
         path::{
             Path,
@@ -23,6 +24,7 @@ use core::{
     fmt,
     fmt::Debug,
 };
+use hashbrown::HashMap;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Proof {
@@ -30,6 +32,27 @@ pub enum Proof {
     Exclusion(ExclusionProof),
 }
 
+/// The reason a [`Proof`] could not be generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum ProofError {
+    /// The leaf is deeper than the limit set with
+    /// [`MerkleTree::with_max_proof_depth`](crate::sparse::MerkleTree::with_max_proof_depth).
+    /// The walk down the tree stops at the limit, so the actual depth of the
+    /// leaf is not known.
+    #[display(fmt = "the proof holds more than the maximum of {} side nodes", max_depth)]
+    DepthExceeded { max_depth: u8 },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProofError {}
+
+/// The kind of a [`Proof`], without the proof data.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ProofKind {
+    Inclusion,
+    Exclusion,
+}
+
 impl Proof {
     pub fn proof_set(&self) -> &ProofSet {
         match self {
@@ -38,18 +61,244 @@ impl Proof {
         }
     }
 
-    pub fn is_inclusion(&self) -> bool {
+    pub fn kind(&self) -> ProofKind {
         match self {
-            Proof::Inclusion(_) => true,
-            Proof::Exclusion(_) => false,
+            Proof::Inclusion(_) => ProofKind::Inclusion,
+            Proof::Exclusion(_) => ProofKind::Exclusion,
         }
     }
 
+    pub fn is_inclusion(&self) -> bool {
+        self.kind() == ProofKind::Inclusion
+    }
+
     pub fn is_exclusion(&self) -> bool {
-        !self.is_inclusion()
+        self.kind() == ProofKind::Exclusion
+    }
+
+    /// The number of levels from the root down to the leaf of the proof, i.e.
+    /// the length of the proof set.
+    pub fn depth(&self) -> usize {
+        self.proof_set().len()
+    }
+
+    /// The largest size, in bytes, of the encoding of a proof returned by
+    /// [`to_bytes`](Self::to_bytes).
+    ///
+    /// It is the size of an exclusion proof holding a leaf and 256 side nodes:
+    /// the tag takes 1 byte, the leaf 64 and the side nodes 32 each.
+    pub const MAX_ENCODED_SIZE: usize = 8257;
+
+    /// The size, in bytes, of the encoding of the proof returned by
+    /// [`to_bytes`](Self::to_bytes).
+    pub fn encoded_size(&self) -> usize {
+        let leaf_size = match self {
+            Proof::Exclusion(ExclusionProof {
+                leaf: ExclusionLeaf::Leaf(_),
+                ..
+            }) => 64,
+            _ => 0,
+        };
+        self.depth()
+            .saturating_mul(32)
+            .saturating_add(leaf_size)
+            .saturating_add(1)
+    }
+
+    /// Encodes the proof as a tag byte, followed by the key and the value hash
+    /// of the leaf of an exclusion proof, if any, and the side nodes of the
+    /// proof set.
+    ///
+    /// The tag is `0` for an inclusion proof, `1` for an exclusion proof of a
+    /// leaf, and `2` for an exclusion proof of a placeholder.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.encoded_size());
+        match self {
+            Proof::Inclusion(_) => bytes.push(0),
+            Proof::Exclusion(proof) => match &proof.leaf {
+                ExclusionLeaf::Leaf(data) => {
+                    bytes.push(1);
+                    bytes.extend_from_slice(&data.leaf_key);
+                    bytes.extend_from_slice(&data.leaf_value);
+                }
+                ExclusionLeaf::Placeholder => bytes.push(2),
+            },
+        }
+        for side_node in self.proof_set() {
+            bytes.extend_from_slice(side_node);
+        }
+        bytes
+    }
+
+    /// The number of side nodes of the proof set that are not placeholders.
+    pub fn num_siblings(&self) -> usize {
+        self.proof_set()
+            .iter()
+            .filter(|side_node| *side_node != zero_sum())
+            .count()
+    }
+
+    /// Pairs each side node of the proof set with the position of that node in
+    /// the tree, assuming the proof was generated for `key`.
+    ///
+    /// The items are yielded in the order of the proof set, from the side node
+    /// closest to the leaf up to the child of the root.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the proof set holds more than 256 nodes, which is never the
+    /// case for a valid proof.
+    pub fn with_positions(
+        &self,
+        key: &MerkleTreeKey,
+    ) -> impl Iterator<Item = (NodePosition, Bytes32)> + '_ {
+        let key = *key.as_bytes();
+        let proof_set = self.proof_set();
+        assert!(proof_set.len() <= 256, "The proof set is too long");
+        let depths = (1..=proof_set.len()).rev();
+        depths.zip(proof_set).map(move |(depth, side_node)| {
+            let depth = u32::try_from(depth).expect("We've checked it above");
+            (NodePosition::side_node(&key, depth), *side_node)
+        })
+    }
+
+    /// Replace the placeholder side nodes of the proof set with the bits of a
+    /// bitmask. See [`OptimizedProof`].
+    pub fn optimize(&self) -> OptimizedProof {
+        let mut placeholders = Bytes32::default();
+        let mut proof_set = ProofSet::new();
+        for (index, side_node) in self.proof_set().iter().enumerate() {
+            // Proof sets of valid proofs hold at most 256 side nodes, so only
+            // the side nodes of an invalid proof can lack a bit
+            if side_node == zero_sum() && index < 256 {
+                #[allow(clippy::arithmetic_side_effects)] // index < 256
+                {
+                    placeholders[index / 8] |= 0x80 >> (index % 8);
+                }
+            } else {
+                proof_set.push(*side_node);
+            }
+        }
+        OptimizedProof {
+            placeholders,
+            proof: self.with_proof_set(proof_set),
+        }
+    }
+
+    fn with_proof_set(&self, proof_set: ProofSet) -> Self {
+        match self {
+            Proof::Inclusion(_) => Proof::Inclusion(InclusionProof { proof_set }),
+            Proof::Exclusion(proof) => Proof::Exclusion(ExclusionProof {
+                proof_set,
+                leaf: proof.leaf.clone(),
+            }),
+        }
     }
 }
 
+/// A [`Proof`] whose placeholder side nodes are replaced by the bits of a
+/// bitmask, produced by [`Proof::optimize`].
+///
+/// A side node is a placeholder, i.e. the zero sum, wherever the sibling
+/// subtree holds no leaves. This is the case for most of the side nodes on the
+/// path to leaves whose keys share a long prefix, and each of them only takes
+/// a bit instead of 32 bytes.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OptimizedProof {
+    /// Bit `i`, counted from the most significant bit, is set if the side node
+    /// `i` of the original proof set is a placeholder.
+    pub placeholders: Bytes32,
+    /// The proof, holding the side nodes that are not placeholders.
+    pub proof: Proof,
+}
+
+impl OptimizedProof {
+    /// Restore the proof, reinserting the placeholder side nodes.
+    pub fn decompress(&self) -> Proof {
+        let mut side_nodes = self.proof.proof_set().iter();
+        let mut proof_set = ProofSet::new();
+        for index in 0u32.. {
+            if self.placeholders.is_msb_set(index) {
+                proof_set.push(*zero_sum());
+            } else if let Some(side_node) = side_nodes.next() {
+                proof_set.push(*side_node);
+            } else {
+                break
+            }
+        }
+        self.proof.with_proof_set(proof_set)
+    }
+}
+
+/// The position of a node in a sparse Merkle tree.
+///
+/// A node at `depth` is reached from the root by following the leading `depth`
+/// bits of `path`, where a `0` bit is a step to the left child; the remaining
+/// bits of `path` are zero. The root is at depth `0`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct NodePosition {
+    depth: u32,
+    path: Bytes32,
+}
+
+impl NodePosition {
+    /// The position of the side node at `depth` on the path to `key`, i.e. the
+    /// sibling of the node at `depth` on that path.
+    fn side_node(key: &Bytes32, depth: u32) -> Self {
+        let mut path = *key;
+        for (offset, byte) in (0u32..).step_by(8).zip(path.iter_mut()) {
+            let kept_bits = depth.saturating_sub(offset);
+            *byte &= !0xffu8.checked_shr(kept_bits).unwrap_or(0);
+        }
+        #[allow(clippy::arithmetic_side_effects)] // The depth is in 1..=256
+        {
+            let index = depth - 1;
+            path[(index / 8) as usize] ^= 0x80 >> (index % 8);
+        }
+        Self { depth, path }
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn path(&self) -> &Bytes32 {
+        &self.path
+    }
+}
+
+/// The side of the child node on the path to a leaf, at one step of the path.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Direction {
+    /// The path node is the left child, and its parent is the hash of the path
+    /// node followed by the sibling.
+    Left,
+    /// The path node is the right child, and its parent is the hash of the
+    /// sibling followed by the path node.
+    Right,
+}
+
+impl From<Side> for Direction {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Left => Direction::Left,
+            Side::Right => Direction::Right,
+        }
+    }
+}
+
+/// One step of the path from a leaf to the root, produced by
+/// [`MerkleTree::path`](crate::sparse::MerkleTree::path).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PathStep {
+    /// The position of the sibling of the path node.
+    pub position: NodePosition,
+    /// The hash of the sibling of the path node.
+    pub sibling_hash: Bytes32,
+    /// The side of the path node.
+    pub direction: Direction,
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub struct InclusionProof {
     pub proof_set: ProofSet,
@@ -164,6 +413,174 @@ impl Debug for ExclusionProof {
     }
 }
 
+/// The part of a [`Proof`] leading from a leaf up to one of its ancestors,
+/// produced by
+/// [`MerkleTree::generate_subpath_proof`](crate::sparse::MerkleTree::generate_subpath_proof).
+///
+/// Proofs of keys below the same ancestor share the side nodes above it, so
+/// a batch of such proofs can carry the side nodes above the ancestor once.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubpathProof {
+    /// The depth of the ancestor, where depth 0 is the root. If the leaf lies
+    /// above the requested depth, this is the depth of the leaf and the proof
+    /// set is empty.
+    pub ancestor_depth: u8,
+    /// The proof, holding the side nodes from the leaf up to, but excluding,
+    /// the ancestor.
+    pub proof: Proof,
+}
+
+impl SubpathProof {
+    pub(crate) fn new(mut proof: Proof, ancestor_depth: u8) -> Self {
+        let proof_set = match &mut proof {
+            Proof::Inclusion(proof) => &mut proof.proof_set,
+            Proof::Exclusion(proof) => &mut proof.proof_set,
+        };
+        let leaf_depth = proof_set.len();
+        // The side nodes are ordered from the leaf upwards; the ones below the
+        // ancestor come first.
+        proof_set.truncate(leaf_depth.saturating_sub(usize::from(ancestor_depth)));
+        let ancestor_depth = u8::try_from(leaf_depth)
+            .map_or(ancestor_depth, |leaf_depth| leaf_depth.min(ancestor_depth));
+        Self {
+            ancestor_depth,
+            proof,
+        }
+    }
+
+    /// Verifies that the subpath leads from the leaf of `key` to `ancestor`.
+    /// The `value` is ignored for exclusion proofs.
+    pub fn verify(&self, ancestor: &Bytes32, key: &MerkleTreeKey, value: &[u8]) -> bool {
+        let (mut current, proof_set) = match &self.proof {
+            Proof::Inclusion(proof) => {
+                (calculate_leaf_hash(key, &sum(value)), &proof.proof_set)
+            }
+            Proof::Exclusion(proof) => {
+                if let ExclusionLeaf::Leaf(data) = &proof.leaf
+                    && data.leaf_key == key.as_ref()
+                {
+                    return false
+                }
+                (proof.leaf.hash(), &proof.proof_set)
+            }
+        };
+
+        let ancestor_depth = u32::from(self.ancestor_depth);
+        let Some(leaf_depth) = u32::try_from(proof_set.len())
+            .ok()
+            .and_then(|len| len.checked_add(ancestor_depth))
+            .filter(|depth| *depth <= 256)
+        else {
+            return false
+        };
+
+        // The side node at depth `d` is on the side given by the bit `d - 1`
+        // of the key.
+        for (index, side_hash) in (ancestor_depth..leaf_depth).rev().zip(proof_set) {
+            current = match key.get_instruction(index).expect("Infallible") {
+                Side::Left => calculate_node_hash(&current, side_hash),
+                Side::Right => calculate_node_hash(side_hash, &current),
+            };
+        }
+        current == *ancestor
+    }
+
+    /// Completes the subpath into a proof up to the root, given the side nodes
+    /// from the ancestor up to the root, i.e. the last
+    /// [`ancestor_depth`](Self::ancestor_depth) side nodes of the full proof.
+    ///
+    /// Returns `None` if `ancestor_proof_set` doesn't hold exactly
+    /// `ancestor_depth` side nodes.
+    pub fn into_proof(self, ancestor_proof_set: &[Bytes32]) -> Option<Proof> {
+        if ancestor_proof_set.len() != usize::from(self.ancestor_depth) {
+            return None
+        }
+        let mut proof = self.proof;
+        match &mut proof {
+            Proof::Inclusion(proof) => {
+                proof.proof_set.extend_from_slice(ancestor_proof_set)
+            }
+            Proof::Exclusion(proof) => {
+                proof.proof_set.extend_from_slice(ancestor_proof_set)
+            }
+        }
+        Some(proof)
+    }
+}
+
+/// Verify a batch of proofs against the same `root`, returning the result of
+/// each item in order. The value of an item is ignored for exclusion proofs.
+///
+/// The results are identical to verifying each proof on its own. Proofs of keys
+/// sharing a path prefix pass through the same ancestor nodes, so the path from
+/// a shared ancestor to the root is only hashed once for the whole batch.
+pub fn verify_proof_batch(
+    root: &Bytes32,
+    items: &[(MerkleTreeKey, &[u8], &Proof)],
+) -> Vec<bool> {
+    // The nodes computed while verifying previous items, indexed by depth and
+    // hash, with the key and the proof set leading from the node to the root,
+    // and the outcome of the verification.
+    let mut visited: HashMap<(usize, Bytes32), (&MerkleTreeKey, &[Bytes32], bool)> =
+        HashMap::new();
+
+    items
+        .iter()
+        .map(|(key, value, proof)| {
+            let (mut current, proof_set) = match proof {
+                Proof::Inclusion(proof) => {
+                    (calculate_leaf_hash(key, &sum(value)), &proof.proof_set)
+                }
+                Proof::Exclusion(proof) => {
+                    if let ExclusionLeaf::Leaf(data) = &proof.leaf
+                        && data.leaf_key == key.as_ref()
+                    {
+                        return false
+                    }
+                    (proof.leaf.hash(), &proof.proof_set)
+                }
+            };
+
+            if proof_set.len() > 256usize {
+                return false
+            }
+
+            let mut path = Vec::with_capacity(proof_set.len());
+            let mut shared_result = None;
+            for (i, side_hash) in proof_set.iter().enumerate() {
+                #[allow(clippy::arithmetic_side_effects)] // i < proof_set.len()
+                let depth = proof_set.len() - i;
+                let remaining = &proof_set[i..];
+
+                // The outcome from here on only depends on the key bits above
+                // this node and the remaining proof set.
+                if let Some((other_key, other_remaining, result)) =
+                    visited.get(&(depth, current))
+                    && *other_remaining == remaining
+                    && key.common_path_length(other_key.as_bytes()) >= depth as u64
+                {
+                    shared_result = Some(*result);
+                    break
+                }
+                path.push((depth, current, remaining));
+
+                #[allow(clippy::arithmetic_side_effects)] // depth >= 1
+                let index = u32::try_from(depth - 1).expect("We've checked it above");
+                current = match key.get_instruction(index).expect("Infallible") {
+                    Side::Left => calculate_node_hash(&current, side_hash),
+                    Side::Right => calculate_node_hash(side_hash, &current),
+                };
+            }
+
+            let result = shared_result.unwrap_or(current == *root);
+            for (depth, node, remaining) in path {
+                visited.insert((depth, node), (key, remaining, result));
+            }
+            result
+        })
+        .collect()
+}
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod test {
@@ -171,13 +588,28 @@ mod test {
         common::{
             Bytes32,
             StorageMap,
+            sum,
         },
         sparse::{
             MerkleTree,
+            MerkleTreeKey,
             Primitive,
-            proof::Proof,
+            hash::{
+                calculate_leaf_hash,
+                calculate_node_hash,
+            },
+            proof::{
+                Direction,
+                ExclusionLeaf,
+                ExclusionLeafData,
+                ExclusionProof,
+                Proof,
+                verify_proof_batch,
+            },
         },
     };
+    use crate::common::Msb;
+    use alloc::vec::Vec;
     use fuel_storage::Mappable;
 
     #[derive(Debug)]
@@ -607,6 +1039,211 @@ mod test {
         // Then
         assert!(exclusion);
     }
+
+    #[test]
+    fn verify_proof_batch__returns_the_result_of_individual_verification() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        for i in 0u32..80 {
+            tree.insert(MerkleTreeKey::new(i.to_be_bytes()), b"DATA").unwrap();
+        }
+        let root = tree.root();
+
+        // 80 included keys, with every 10th one given the wrong value, and 20
+        // excluded keys
+        let keys = (0u32..100)
+            .map(|i| MerkleTreeKey::new(i.to_be_bytes()))
+            .collect::<Vec<_>>();
+        let proofs = keys
+            .iter()
+            .map(|key| tree.generate_proof(key).unwrap())
+            .collect::<Vec<_>>();
+        let items = keys
+            .iter()
+            .zip(proofs.iter())
+            .enumerate()
+            .map(|(i, (key, proof))| {
+                let value: &[u8] = if i % 10 == 3 { b"WRONG" } else { b"DATA" };
+                (*key, value, proof)
+            })
+            .collect::<Vec<_>>();
+
+        let expected = items
+            .iter()
+            .map(|(key, value, proof)| match proof {
+                Proof::Inclusion(proof) => proof.verify(&root, key, value),
+                Proof::Exclusion(proof) => proof.verify(&root, key),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(expected.iter().filter(|result| **result).count(), 92);
+
+        assert_eq!(verify_proof_batch(&root, &items), expected);
+    }
+
+    #[test]
+    fn proof__with_positions__yields_the_side_nodes_along_the_path_to_the_key() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        for i in 0u32..64 {
+            tree.insert(MerkleTreeKey::new(i.to_be_bytes()), b"DATA")
+                .expect("Expected successful update");
+        }
+
+        let included = MerkleTreeKey::new(7u32.to_be_bytes());
+        let excluded = MerkleTreeKey::new(64u32.to_be_bytes());
+        for key in [included, excluded] {
+            let proof = tree.generate_proof(&key).unwrap();
+            let positions = proof.with_positions(&key).collect::<Vec<_>>();
+            assert_eq!(positions.len(), proof.proof_set().len());
+
+            let depths = (1..=u32::try_from(positions.len()).unwrap()).rev();
+            for ((position, _), depth) in positions.iter().zip(depths) {
+                assert_eq!(position.depth(), depth);
+                // The side node branches off the path to the key at its depth
+                let path = position.path();
+                let branch = depth.checked_sub(1).unwrap();
+                assert_eq!(path.common_prefix_count(key.as_ref()), u64::from(branch));
+                for index in depth..256 {
+                    assert_eq!(path.get_bit_at_index_from_msb(index), Some(false));
+                }
+            }
+            let side_nodes = positions.iter().map(|(_, side_node)| *side_node);
+            assert!(side_nodes.eq(proof.proof_set().iter().copied()));
+        }
+    }
+
+    #[test]
+    fn merkle_tree__path__hashes_up_to_the_root() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        for i in 0u32..64 {
+            tree.insert(MerkleTreeKey::new(i.to_be_bytes()), b"DATA")
+                .expect("Expected successful update");
+        }
+
+        let key = MerkleTreeKey::new(7u32.to_be_bytes());
+        let path = tree.path(&key).unwrap();
+        let proof = tree.generate_proof(&key).unwrap();
+        assert_eq!(path.len(), proof.proof_set().len());
+
+        let mut current = calculate_leaf_hash(&key, &sum(b"DATA"));
+        for (step, (position, side_node)) in path.iter().zip(proof.with_positions(&key)) {
+            assert_eq!(step.position, position);
+            assert_eq!(step.sibling_hash, side_node);
+            current = match step.direction {
+                Direction::Left => calculate_node_hash(&current, &step.sibling_hash),
+                Direction::Right => calculate_node_hash(&step.sibling_hash, &current),
+            };
+        }
+        assert_eq!(current, tree.root());
+    }
+
+    #[test]
+    fn optimized_proof__decompress__restores_the_proof() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        // Keys differing in their last byte only, whose proofs mostly hold
+        // placeholders
+        for i in [0u8, 1, 2, 0x80, 0xff] {
+            let mut key = [0xab; 32];
+            key[31] = i;
+            tree.insert(MerkleTreeKey::from(key), b"DATA").unwrap();
+        }
+        let mut present = [0xab; 32];
+        present[31] = 0;
+        let mut absent = [0xab; 32];
+        absent[31] = 0x40;
+        let keys = [present, absent, [0u8; 32]].map(MerkleTreeKey::from);
+
+        for key in keys {
+            let proof = tree.generate_proof(&key).unwrap();
+            let optimized = proof.optimize();
+            assert_eq!(optimized.decompress(), proof);
+
+            let placeholders = optimized.placeholders.iter().map(|b| b.count_ones());
+            let placeholders = usize::try_from(placeholders.sum::<u32>()).unwrap();
+            assert_eq!(
+                optimized.proof.proof_set().len() + placeholders,
+                proof.proof_set().len()
+            );
+        }
+
+        let proof = tree.generate_proof(&keys[0]).unwrap();
+        assert_eq!(proof.depth(), 256);
+        assert_eq!(proof.num_siblings(), 3);
+        assert_eq!(proof.optimize().proof.proof_set().len(), proof.num_siblings());
+    }
+
+    #[test]
+    fn proof__encoded_size__is_the_length_of_the_encoding() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        for i in [0u8, 1, 0x80] {
+            tree.insert(MerkleTreeKey::from([i; 32]), b"DATA").unwrap();
+        }
+
+        let keys = [[0u8; 32], [0x40; 32], [0x81; 32]].map(MerkleTreeKey::from);
+        for key in keys {
+            let proof = tree.generate_proof(&key).unwrap();
+            assert_eq!(proof.encoded_size(), proof.to_bytes().len());
+        }
+
+        let largest = Proof::Exclusion(ExclusionProof {
+            proof_set: vec![[0xff; 32]; 256],
+            leaf: ExclusionLeaf::Leaf(ExclusionLeafData {
+                leaf_key: [0xff; 32],
+                leaf_value: [0xff; 32],
+            }),
+        });
+        assert_eq!(largest.to_bytes().len(), Proof::MAX_ENCODED_SIZE);
+        assert_eq!(largest.encoded_size(), Proof::MAX_ENCODED_SIZE);
+    }
+
+    #[test]
+    fn subpath_proof__verify__returns_true_for_the_ancestor_of_the_key() {
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        // K1 and K3 lie below the node with the prefix 010, at depth 3, whose
+        // sibling is K2
+        let key = |byte: u8| {
+            let mut key = [0u8; 32];
+            key[0] = byte;
+            MerkleTreeKey::from(key)
+        };
+        let k0 = key(0b00000000);
+        let k1 = key(0b01000000);
+        let k2 = key(0b01100000);
+        let k3 = key(0b01001000);
+        for k in [k0, k1, k2, k3] {
+            tree.insert(k, b"DATA").expect("Expected successful update");
+        }
+        let ancestor = tree.generate_proof(&k2).unwrap().proof_set()[0];
+
+        let absent = key(0b01010000);
+        for k in [k1, k3, absent] {
+            let subpath = tree.generate_subpath_proof(&k, 3).unwrap();
+            assert_eq!(subpath.ancestor_depth, 3);
+            assert!(subpath.verify(&ancestor, &k, b"DATA"));
+            assert!(!subpath.verify(&tree.root(), &k, b"DATA"));
+
+            let proof = tree.generate_proof(&k).unwrap();
+            let upper = &proof.proof_set()[subpath.proof.proof_set().len()..];
+            assert_eq!(subpath.into_proof(upper), Some(proof));
+        }
+
+        let subpath = tree.generate_subpath_proof(&k1, 3).unwrap();
+        assert!(!subpath.verify(&ancestor, &k1, b"OTHER"));
+        let subpath = tree.generate_subpath_proof(&k1, 0).unwrap();
+        assert!(subpath.verify(&tree.root(), &k1, b"DATA"));
+
+        // K0 is a leaf at depth 2, above the requested ancestor
+        let subpath = tree.generate_subpath_proof(&k0, 4).unwrap();
+        assert_eq!(subpath.ancestor_depth, 2);
+        assert!(subpath.proof.proof_set().is_empty());
+        assert_eq!(subpath.into_proof(&[]), None);
+    }
 }
 
 #[cfg(test)]
@@ -621,7 +1258,10 @@ mod test_random {
             MerkleTree,
             MerkleTreeKey,
             Primitive,
-            proof::Proof,
+            proof::{
+                Proof,
+                ProofKind,
+            },
         },
     };
     use fuel_storage::Mappable;
@@ -797,4 +1437,43 @@ mod test_random {
         // Then
         assert!(exclusion);
     }
+
+    #[test]
+    fn proof__kind__returns_inclusion_for_included_key() {
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+
+        // Given
+        let key: MerkleTreeKey = random_bytes32(&mut rng).into();
+        tree.insert(key, b"DATA").unwrap();
+
+        // When
+        let proof = tree.generate_proof(&key).unwrap();
+
+        // Then
+        assert_eq!(proof.kind(), ProofKind::Inclusion);
+        assert!(proof.is_inclusion());
+        assert!(!proof.is_exclusion());
+    }
+
+    #[test]
+    fn proof__kind__returns_exclusion_for_excluded_key() {
+        let mut rng = StdRng::seed_from_u64(0xDEADBEEF);
+        let mut storage = StorageMap::<TestTable>::new();
+        let mut tree = MerkleTree::new(&mut storage);
+        tree.insert(random_bytes32(&mut rng).into(), b"DATA")
+            .unwrap();
+
+        // Given
+        let key: MerkleTreeKey = random_bytes32(&mut rng).into();
+
+        // When
+        let proof = tree.generate_proof(&key).unwrap();
+
+        // Then
+        assert_eq!(proof.kind(), ProofKind::Exclusion);
+        assert!(proof.is_exclusion());
+        assert!(!proof.is_inclusion());
+    }
 }
@@ -0,0 +1,45 @@
+//! Helpers to generate random trees and leaf sets in tests.
+
+use crate::{
+    binary,
+    common::Bytes32,
+    sparse::{
+        self,
+        MerkleTreeKey,
+    },
+};
+use alloc::vec::Vec;
+use rand::Rng;
+
+/// Generate `n` random leaves, each with a hashed random key and 32 bytes of
+/// random data.
+pub fn random_leaf_set<R: Rng>(rng: &mut R, n: usize) -> Vec<(MerkleTreeKey, Vec<u8>)> {
+    (0..n)
+        .map(|_| {
+            let key = MerkleTreeKey::new(rng.r#gen::<Bytes32>());
+            let data = rng.r#gen::<Bytes32>().to_vec();
+            (key, data)
+        })
+        .collect()
+}
+
+/// Generate an in-memory sparse Merkle tree with `size` random leaves.
+pub fn random_sparse_tree<R: Rng>(
+    rng: &mut R,
+    size: usize,
+) -> sparse::in_memory::MerkleTree {
+    sparse::in_memory::MerkleTree::from_set(random_leaf_set(rng, size).into_iter())
+}
+
+/// Generate an in-memory binary Merkle tree with `size` leaves of 32 bytes of
+/// random data.
+pub fn random_binary_tree<R: Rng>(
+    rng: &mut R,
+    size: usize,
+) -> binary::in_memory::MerkleTree {
+    let mut tree = binary::in_memory::MerkleTree::new();
+    for _ in 0..size {
+        tree.push(&rng.r#gen::<Bytes32>());
+    }
+    tree
+}
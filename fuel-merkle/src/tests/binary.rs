@@ -8,6 +8,7 @@ use rand::{
 
 use crate::{
     binary::{
+        self,
         MerkleTree,
         Primitive,
     },
@@ -16,7 +17,14 @@ use crate::{
         StorageMap,
     },
 };
-use fuel_merkle_test_helpers::binary::MerkleTree as ReferenceMerkleTree;
+use alloc::vec::Vec;
+use fuel_merkle_test_helpers::{
+    TEST_DATA,
+    binary::{
+        self as reference,
+        MerkleTree as ReferenceMerkleTree,
+    },
+};
 use fuel_storage::Mappable;
 
 struct TestTable;
@@ -37,7 +45,7 @@ const SAMPLE_SIZES: &[usize] = &[
 
 fn sum(data: &[u8]) -> Bytes32 {
     let hash = blake3::hash(data);
-    (*hash.as_bytes()).into()
+    *hash.as_bytes()
 }
 
 #[test]
@@ -145,3 +153,32 @@ fn test_load() {
         assert_eq!(root, expected_root);
     }
 }
+
+#[test]
+fn test_hash_sums_match_reference() {
+    // Inputs spanning several BLAKE3 chunks of 1024 bytes
+    let long_data = (0..3000u32)
+        .flat_map(|i| i.to_be_bytes())
+        .collect::<Vec<u8>>();
+    let data = TEST_DATA
+        .iter()
+        .copied()
+        .chain([&[][..], &long_data[..]])
+        .collect::<Vec<&[u8]>>();
+
+    assert_eq!(binary::empty_sum(), reference::empty_sum());
+
+    for datum in data.iter() {
+        assert_eq!(binary::leaf_sum(datum), reference::leaf_sum(datum));
+    }
+
+    let leaves = data
+        .iter()
+        .map(|datum| binary::leaf_sum(datum))
+        .collect::<Vec<Bytes32>>();
+    for lhs in leaves.iter() {
+        for rhs in leaves.iter() {
+            assert_eq!(binary::node_sum(lhs, rhs), reference::node_sum(lhs, rhs));
+        }
+    }
+}
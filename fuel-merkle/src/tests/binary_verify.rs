@@ -26,6 +26,7 @@ use crate::{
         StorageMap,
     },
 };
+use alloc::vec::Vec;
 use fuel_storage::Mappable;
 
 #[derive(Debug)]
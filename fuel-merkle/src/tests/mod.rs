@@ -1,6 +1,10 @@
+// Is used by the proto tests
+use bytes as _;
 use criterion as _;
 // Is used by the data tests
 use datatest_stable as _;
+// Is used by the proto tests
+use prost as _;
 use serde_yaml as _;
 
 mod binary;
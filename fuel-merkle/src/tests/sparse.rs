@@ -20,6 +20,7 @@ use crate::{
 
 use fuel_storage::Mappable;
 
+use alloc::vec::Vec;
 use core::fmt::{
     Debug,
     Formatter,
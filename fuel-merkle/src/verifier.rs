@@ -0,0 +1,110 @@
+use crate::{
+    VerifyError,
+    binary,
+    common::{
+        Bytes32,
+        ProofSet,
+    },
+    sparse::{
+        MerkleTreeKey,
+        proof::Proof,
+    },
+};
+
+/// Verification of the proofs of both kinds of trees.
+///
+/// Light clients checking binary receipt proofs and sparse state proofs can use
+/// this single type instead of the verification functions of each tree.
+#[derive(Debug, Clone, Copy)]
+pub struct Verifier;
+
+impl Verifier {
+    /// Verify that `data` is the leaf at `leaf_index` of the binary Merkle tree
+    /// with `leaf_count` leaves and root `root`.
+    pub fn verify_binary(
+        root: &Bytes32,
+        leaf_index: u64,
+        leaf_count: u64,
+        data: &[u8],
+        proof: &ProofSet,
+    ) -> Result<(), VerifyError> {
+        if leaf_index >= leaf_count {
+            return Err(VerifyError::InvalidProofIndex(leaf_index))
+        }
+        binary::verify(root, &data, proof, leaf_index, leaf_count)
+            .then_some(())
+            .ok_or(VerifyError::RootMismatch)
+    }
+
+    /// Verify a proof of the sparse Merkle tree with root `root`: that `key`
+    /// holds `data` for an inclusion proof, or that `key` is absent for an
+    /// exclusion proof, in which case `data` is ignored.
+    pub fn verify_sparse(
+        root: &Bytes32,
+        key: &MerkleTreeKey,
+        data: &[u8],
+        proof: &Proof,
+    ) -> Result<(), VerifyError> {
+        let verified = match proof {
+            Proof::Inclusion(proof) => proof.verify(root, key, data),
+            Proof::Exclusion(proof) => proof.verify(root, key),
+        };
+        verified.then_some(()).ok_or(VerifyError::RootMismatch)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        binary::in_memory::MerkleTree as BinaryMerkleTree,
+        sparse::in_memory::MerkleTree as SparseMerkleTree,
+    };
+    use fuel_merkle_test_helpers::TEST_DATA;
+
+    #[test]
+    fn verify_binary_checks_the_leaf_against_the_root() {
+        let mut tree = BinaryMerkleTree::new();
+        for datum in TEST_DATA.iter() {
+            tree.push(datum);
+        }
+        let count = TEST_DATA.len() as u64;
+        let (root, proof_set) = tree.prove(3).unwrap();
+
+        assert_eq!(
+            Verifier::verify_binary(&root, 3, count, TEST_DATA[3], &proof_set),
+            Ok(())
+        );
+        assert_eq!(
+            Verifier::verify_binary(&root, 3, count, TEST_DATA[4], &proof_set),
+            Err(VerifyError::RootMismatch)
+        );
+        assert_eq!(
+            Verifier::verify_binary(&root, count, count, TEST_DATA[3], &proof_set),
+            Err(VerifyError::InvalidProofIndex(count))
+        );
+    }
+
+    #[test]
+    fn verify_sparse_checks_inclusion_and_exclusion_proofs() {
+        let mut tree = SparseMerkleTree::new();
+        let included = MerkleTreeKey::new(b"included");
+        let excluded = MerkleTreeKey::new(b"excluded");
+        tree.update(included, b"DATA");
+        let root = tree.root();
+
+        let proof = tree.generate_proof(&included).unwrap();
+        assert_eq!(Verifier::verify_sparse(&root, &included, b"DATA", &proof), Ok(()));
+        assert_eq!(
+            Verifier::verify_sparse(&root, &included, b"OTHER", &proof),
+            Err(VerifyError::RootMismatch)
+        );
+
+        let proof = tree.generate_proof(&excluded).unwrap();
+        assert_eq!(Verifier::verify_sparse(&root, &excluded, b"", &proof), Ok(()));
+        assert_eq!(
+            Verifier::verify_sparse(&root, &included, b"DATA", &proof),
+            Err(VerifyError::RootMismatch)
+        );
+    }
+}
@@ -6,7 +6,7 @@ mod verify;
 pub use merkle_tree::MerkleTree;
 pub use verify::verify;
 
-pub(crate) use hash::{
+pub use hash::{
     Data,
     empty_sum,
     leaf_sum,
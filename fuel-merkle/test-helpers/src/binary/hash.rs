@@ -22,7 +22,7 @@ pub fn node_sum(lhs_data: &[u8], rhs_data: &[u8]) -> Data {
     hasher.update(&[NODE]);
     hasher.update(lhs_data);
     hasher.update(rhs_data);
-    (*hasher.finalize().as_bytes()).into()
+    *hasher.finalize().as_bytes()
 }
 
 // Merkle tree hash of a list with one entry
@@ -31,5 +31,5 @@ pub fn leaf_sum(data: &[u8]) -> Data {
     let mut hasher = blake3::Hasher::new();
     hasher.update(&[LEAF]);
     hasher.update(data);
-    (*hasher.finalize().as_bytes()).into()
+    *hasher.finalize().as_bytes()
 }
@@ -15,7 +15,7 @@ use rand_seeder::Seeder;
 
 pub fn sum(data: &[u8]) -> Bytes32 {
     let hash = blake3::hash(data);
-    (*hash.as_bytes()).into()
+    *hash.as_bytes()
 }
 
 fn generate_test(
@@ -0,0 +1,136 @@
+//! Test vectors for the hashes of the binary Merkle tree nodes.
+//!
+//! The expected digests were computed with the BLAKE3 reference
+//! implementation, and can be used to check other implementations of the
+//! binary Merkle tree for compatibility. A leaf hashes `0x00 || data` and a
+//! node hashes `0x01 || lhs || rhs`.
+
+use fuel_merkle::binary::{
+    leaf_sum,
+    node_sum,
+};
+
+/// `len` bytes of the repeating sequence `0, 1, ..., 250`, the input used by
+/// the official BLAKE3 test vectors.
+fn input(len: usize) -> Vec<u8> {
+    (0..251u8).cycle().take(len).collect()
+}
+
+fn bytes32(hex: &str) -> [u8; 32] {
+    hex::decode(hex)
+        .expect("Valid hex")
+        .try_into()
+        .expect("32 bytes")
+}
+
+#[test]
+fn leaf_sum_matches_reference_vectors() {
+    let vectors = [
+        (0, "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213"),
+        (1, "1ad48f49627079d806b802c74f40c39d55fe1d78b3faf0f8017aec62cec42122"),
+        (2, "add61d217fbd5b908646176d779a5d09998d01394744dc244dfae629ba807425"),
+        (3, "b4a4a09b5cdb64b5d0b701ed3882f49133bf4605736fd4c31b9fef025dec9bac"),
+        (31, "afe8b11088ecf5bfd49a76ed3d9a7c7c56c1ccd789b9e1a0ea804969bda1af8f"),
+        (32, "261112fe15b238a27ce239bfcfd32f08e26ed62777b9a41fb7c778399773fbaa"),
+        (33, "6efb978c04dbc8ef60e5d50d2d7ceae060d354ffb6eb5f292aaaf1a5b334a7e8"),
+        (63, "ef7d4fec13e44b891185774af34bb56006cda95c9165853f2994204dcb2b22ff"),
+        (64, "337aa8b86bb4ec4057747a2c98a2737f12e764d1385bc8d84ab3553f99494332"),
+        (65, "6cc81d4be9874b80b71fb78f06c47af97d7776d127b152184e8bea8563388357"),
+        // With the prefix, the input fills exactly one BLAKE3 chunk
+        (1023, "e23792366f1d2eee455406bf46e1447f963b2a24f6c0f8cae77575e472bb60f4"),
+        (1024, "440fea008dbecd9992742d052b7ea7321e6bd9afc4efee07b66d68d674dfdb31"),
+        (1025, "f1a1bc1e25dfa4260861137b75c40502c3f5ac1ed08766cf4ef367b5c6b1cb07"),
+        (2048, "26c7a53e78a351e0ed4e7b0012a5ab5d1eaa7e99e1b821894decd35f9689f9f8"),
+    ];
+
+    for (len, expected) in vectors {
+        assert_eq!(
+            hex::encode(leaf_sum(&input(len))),
+            expected,
+            "leaf_sum of {len} bytes"
+        );
+    }
+}
+
+#[test]
+fn node_sum_matches_reference_vectors() {
+    const ZEROS: &str =
+        "0000000000000000000000000000000000000000000000000000000000000000";
+    const ONES: &str =
+        "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff";
+    // `input(32)`
+    const SEQUENCE: &str =
+        "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    // The 32 bytes following `input(32)`
+    const NEXT_SEQUENCE: &str =
+        "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f";
+    // The hash of the empty input, i.e. the root of an empty tree
+    const EMPTY: &str =
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
+    // `leaf_sum` of the empty input
+    const EMPTY_LEAF: &str =
+        "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213";
+
+    let vectors = [
+        (
+            ZEROS,
+            ZEROS,
+            "484355d80674e290ecbca3d2a29c948912537bbfcdd930aeaaf6037d1a98f7c0",
+        ),
+        (
+            ZEROS,
+            ONES,
+            "fb540723cfdad42bb16c920569d51be26b83383e62c91efe0d5242184be48e42",
+        ),
+        (
+            ONES,
+            ZEROS,
+            "64e0798273460d792e32492769be4641b74028bcf9c46d6d50fb813f85e3b4bd",
+        ),
+        (
+            ONES,
+            ONES,
+            "fd2ac06f00b7d47da8b61d538a4022c149b6be089bc573c0049c2396bb7f9a45",
+        ),
+        (
+            SEQUENCE,
+            NEXT_SEQUENCE,
+            "0d4b63e647bc83dd9ec61dd4ecfc1cf8e2dbbd785f92680e0540928ab78ee1f0",
+        ),
+        (
+            NEXT_SEQUENCE,
+            SEQUENCE,
+            "b65fc32ff791c520e4b3b1c01d3c402d6c343bc46e7674d733c099472f07baba",
+        ),
+        (
+            EMPTY,
+            EMPTY,
+            "79434797638924c3bc1613de5f38a0ccfe513fc4ec1a8ba97f9303cfa875c103",
+        ),
+        (
+            EMPTY_LEAF,
+            EMPTY_LEAF,
+            "5f704d5811df1b9640225897ddad499059e2b04e93b4df78ed3e97e6126b7cde",
+        ),
+        (
+            ZEROS,
+            SEQUENCE,
+            "e1e3f75c775f7b5e5b9f9c6f439be1b50df4470a06737757db68627175624118",
+        ),
+        (
+            SEQUENCE,
+            ONES,
+            "fd35e443ea1b0aee18af8ecb16d57b9ef8e3f4abbfce99e955d96ca7f33bf5d3",
+        ),
+    ];
+
+    assert_eq!(hex::encode(input(32)), SEQUENCE);
+    assert_eq!(hex::encode(leaf_sum(&[])), EMPTY_LEAF);
+    for (lhs, rhs, expected) in vectors {
+        assert_eq!(
+            hex::encode(node_sum(&bytes32(lhs), &bytes32(rhs))),
+            expected,
+            "node_sum of {lhs} and {rhs}"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+#![allow(non_snake_case)]
+
+use bytes::Bytes;
+use fuel_merkle::{
+    common::Bytes32,
+    proto,
+    sparse::{
+        MerkleTreeKey,
+        in_memory::MerkleTree,
+        proof::Proof,
+    },
+};
+use prost::Message;
+use std::{
+    sync::mpsc,
+    thread,
+};
+
+fn send_over_channel(message: Bytes) -> Bytes {
+    let (sender, receiver) = mpsc::channel::<Bytes>();
+    let handle = thread::spawn(move || receiver.recv().expect("Expected a message"));
+    sender.send(message).expect("Receiver is alive");
+    handle.join().expect("Receiver panicked")
+}
+
+fn roundtrip(proof: Proof, key: MerkleTreeKey) -> (Proof, Bytes32) {
+    let proof = Bytes::from(proto::Proof::from(proof).encode_to_vec());
+    let key = Bytes::from(proto::MerkleTreeKey::from(key).encode_to_vec());
+
+    let proof = proto::Proof::decode(send_over_channel(proof)).expect("Valid proof");
+    let key = proto::MerkleTreeKey::decode(send_over_channel(key)).expect("Valid key");
+
+    (
+        proof.try_into().expect("Valid native proof"),
+        proto::key_path(&key).expect("Valid key path"),
+    )
+}
+
+#[test]
+fn inclusion_proof__received_over_channel__verifies() {
+    let set = (0u32..10).map(|i| (MerkleTreeKey::new(i.to_be_bytes()), b"DATA"));
+    let tree = MerkleTree::from_set(set);
+    let root = tree.root();
+    let key = MerkleTreeKey::new(3u32.to_be_bytes());
+    let proof = tree.generate_proof(&key).expect("Expected a proof");
+
+    let (received_proof, received_path) = roundtrip(proof.clone(), key);
+
+    assert_eq!(received_proof, proof);
+    assert_eq!(received_path, *key);
+    let Proof::Inclusion(proof) = received_proof else {
+        panic!("Expected an inclusion proof")
+    };
+    assert!(proof.verify(&root, &key, b"DATA"));
+}
+
+#[test]
+fn exclusion_proof__received_over_channel__verifies() {
+    let set = (0u32..10).map(|i| (MerkleTreeKey::new(i.to_be_bytes()), b"DATA"));
+    let tree = MerkleTree::from_set(set);
+    let root = tree.root();
+    let key = MerkleTreeKey::new(42u32.to_be_bytes());
+    let proof = tree.generate_proof(&key).expect("Expected a proof");
+
+    let (received_proof, received_path) = roundtrip(proof.clone(), key);
+
+    assert_eq!(received_proof, proof);
+    assert_eq!(received_path, *key);
+    let Proof::Exclusion(proof) = received_proof else {
+        panic!("Expected an exclusion proof")
+    };
+    assert!(proof.verify(&root, &key));
+}
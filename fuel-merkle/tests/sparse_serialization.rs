@@ -0,0 +1,83 @@
+#![allow(non_snake_case)]
+
+use fuel_merkle::{
+    common::Bytes32,
+    sparse::{
+        MerkleTreeKey,
+        Primitive,
+        in_memory::MerkleTree,
+        proof::Proof,
+    },
+};
+use rand::{
+    Rng,
+    SeedableRng,
+    rngs::StdRng,
+};
+
+/// Size of a serialized node: its key, followed by the height, prefix and
+/// the two 32-byte halves of its primitive
+const NODE_SIZE: usize = 32 + 4 + 1 + 32 + 32;
+
+fn leaf(i: u32) -> (MerkleTreeKey, [u8; 4]) {
+    (MerkleTreeKey::new(i.to_be_bytes()), i.to_le_bytes())
+}
+
+fn serialize(nodes: &[(Bytes32, Primitive)]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(nodes.len() * NODE_SIZE);
+    for (key, (height, prefix, bytes_lo, bytes_hi)) in nodes {
+        bytes.extend_from_slice(key);
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.push(*prefix);
+        bytes.extend_from_slice(bytes_lo);
+        bytes.extend_from_slice(bytes_hi);
+    }
+    bytes
+}
+
+fn deserialize(bytes: &[u8]) -> Vec<(Bytes32, Primitive)> {
+    assert_eq!(bytes.len() % NODE_SIZE, 0, "Expected whole nodes");
+    bytes
+        .chunks_exact(NODE_SIZE)
+        .map(|node| {
+            let (key, node) = node.split_at(32);
+            let (height, node) = node.split_at(4);
+            let (prefix, node) = node.split_at(1);
+            let (bytes_lo, bytes_hi) = node.split_at(32);
+            let primitive = (
+                u32::from_be_bytes(height.try_into().unwrap()),
+                prefix[0],
+                bytes_lo.try_into().unwrap(),
+                bytes_hi.try_into().unwrap(),
+            );
+            (key.try_into().unwrap(), primitive)
+        })
+        .collect()
+}
+
+#[test]
+fn sparse_tree__serialized_and_deserialized__generates_proofs_for_the_original_root() {
+    let original = MerkleTree::from_set((0u32..1000).map(leaf));
+    let root = original.root();
+
+    let (nodes_root, nodes) = MerkleTree::nodes_from_set((0u32..1000).map(leaf));
+    assert_eq!(nodes_root, root);
+    let bytes = serialize(&nodes);
+    let deserialized =
+        MerkleTree::from_nodes(nodes_root, deserialize(&bytes)).expect("Valid nodes");
+
+    assert_eq!(deserialized.root(), root);
+
+    let rng = &mut StdRng::seed_from_u64(0xdead_beef);
+    for _ in 0..10 {
+        let (key, value) = leaf(rng.gen_range(0..1000));
+        let original_proof = original.generate_proof(&key).expect("Expected a proof");
+        let proof = deserialized.generate_proof(&key).expect("Expected a proof");
+
+        assert_eq!(proof, original_proof);
+        let Proof::Inclusion(proof) = proof else {
+            panic!("Expected an inclusion proof")
+        };
+        assert!(proof.verify(&root, &key, &value));
+    }
+}
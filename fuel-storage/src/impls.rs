@@ -64,6 +64,14 @@ impl<T: StorageMutate<Type> + ?Sized, Type: Mappable> StorageMutate<Type> for &'
         <T as StorageMutate<Type>>::replace(self, key, value)
     }
 
+    fn upsert(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        <T as StorageMutate<Type>>::upsert(self, key, value)
+    }
+
     fn remove(&mut self, key: &Type::Key) -> Result<(), Self::Error> {
         <T as StorageMutate<Type>>::remove(self, key)
     }
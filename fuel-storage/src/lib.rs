@@ -96,6 +96,19 @@ pub trait StorageMutate<Type: Mappable>: StorageInspect<Type> {
         value: &Type::Value,
     ) -> Result<Option<Type::OwnedValue>, Self::Error>;
 
+    /// Insert the `Key->Value` mapping, or update the value if `Key` is already
+    /// mapped.
+    ///
+    /// Return the previous value as `Ok(Some(Value))`, or `Ok(None)` if `Key`
+    /// was absent. Prefer it over checking `contains_key` before writing.
+    fn upsert(
+        &mut self,
+        key: &Type::Key,
+        value: &Type::Value,
+    ) -> Result<Option<Type::OwnedValue>, Self::Error> {
+        self.replace(key, value)
+    }
+
     /// Remove `Key->Value` mapping from the storage.
     fn remove(&mut self, key: &Type::Key) -> Result<(), Self::Error> {
         self.take(key).map(|_| ())
@@ -116,10 +116,10 @@ impl Contract {
     pub fn id(salt: &Salt, root: &Bytes32, state_root: &Bytes32) -> ContractId {
         let mut hasher = Hasher::default();
 
-        hasher.input(ContractId::SEED);
-        hasher.input(salt);
-        hasher.input(root);
-        hasher.input(state_root);
+        hasher.update(ContractId::SEED);
+        hasher.update(salt);
+        hasher.update(root);
+        hasher.update(state_root);
 
         ContractId::from(*hasher.digest())
     }
@@ -245,7 +245,7 @@ impl Receipt {
         is: Word,
         data: Vec<u8>,
     ) -> Self {
-        let digest = Hasher::hash(&data);
+        let digest = Hasher::hash(&data).into();
         Self::return_data_with_len(
             id,
             ptr,
@@ -336,7 +336,7 @@ impl Receipt {
         is: Word,
         data: Vec<u8>,
     ) -> Self {
-        let digest = Hasher::hash(&data);
+        let digest = Hasher::hash(&data).into();
         Self::log_data_with_len(
             id,
             ra,
@@ -408,7 +408,7 @@ mod use_std {
             let variant = self.rng.gen_range(0..UpgradePurpose::COUNT);
             let consensus_params =
                 postcard::to_allocvec(&ConsensusParameters::default()).unwrap();
-            let checksum = Hasher::hash(consensus_params.as_slice());
+            let checksum = Hasher::hash(consensus_params.as_slice()).into();
 
             let purpose = match variant {
                 0 => UpgradePurpose::StateTransition {
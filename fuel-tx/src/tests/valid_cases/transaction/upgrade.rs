@@ -662,7 +662,7 @@ fn check__errors_when_consensus_parameters_unable_decode_consensus_parameters()
         .finalize();
     *tx.upgrade_purpose_mut() = UpgradePurpose::ConsensusParameters {
         witness_index: 0,
-        checksum: Hasher::hash(serialized_consensus_parameters.as_slice()),
+        checksum: Hasher::hash(serialized_consensus_parameters.as_slice()).into(),
     };
 
     // When
@@ -689,7 +689,7 @@ fn check__errors_when_consensus_parameters_different_than_calculated_metadata()
         .finalize();
     *tx.upgrade_purpose_mut() = UpgradePurpose::ConsensusParameters {
         witness_index: 0,
-        checksum: Hasher::hash(serialized_consensus_parameters.as_slice()),
+        checksum: Hasher::hash(serialized_consensus_parameters.as_slice()).into(),
     };
 
     // When
@@ -240,7 +240,7 @@ impl Transaction {
             .map_err(|_| {
                 ValidityError::TransactionUpgradeConsensusParametersSerialization
             })?;
-        let checksum = Hasher::hash(&serialized_consensus_parameters);
+        let checksum = Hasher::hash(&serialized_consensus_parameters).into();
         let witness_index = u16::try_from(witnesses.len())
             .map_err(|_| ValidityError::TransactionWitnessesMax)?;
         witnesses.push(serialized_consensus_parameters.into());
@@ -60,8 +60,8 @@ pub fn compute_transaction_id<T: fuel_types::canonical::Serialize>(
 ) -> crate::TxId {
     let mut hasher = fuel_crypto::Hasher::default();
     // chain ID
-    hasher.input(chain_id.to_be_bytes());
+    hasher.update(chain_id.to_be_bytes());
     // transaction bytes
-    hasher.input(tx.to_bytes().as_slice());
-    hasher.finalize()
+    hasher.update(tx.to_bytes().as_slice());
+    hasher.finalize().into()
 }
@@ -765,8 +765,8 @@ impl Input {
 
         let mut hasher = Hasher::default();
 
-        hasher.input(ContractId::SEED);
-        hasher.input(root);
+        hasher.update(ContractId::SEED);
+        hasher.update(root);
 
         (*hasher.digest()).into()
     }
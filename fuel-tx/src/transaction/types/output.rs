@@ -208,7 +208,7 @@ impl Output {
     }
 
     pub fn message_digest(data: &[u8]) -> Bytes32 {
-        Hasher::hash(data)
+        Hasher::hash(data).into()
     }
 
     /// Empties fields that should be zero during the signing.
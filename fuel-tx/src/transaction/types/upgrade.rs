@@ -60,7 +60,8 @@ impl UpgradeMetadata {
                     .ok_or(ValidityError::InputWitnessIndexBounds { index })?;
 
                 let serialized_consensus_parameters = witness.as_vec();
-                let actual_checksum = Hasher::hash(serialized_consensus_parameters);
+                let actual_checksum: Bytes32 =
+                    Hasher::hash(serialized_consensus_parameters).into();
 
                 if &actual_checksum != checksum {
                     Err(ValidityError::TransactionUpgradeConsensusParametersChecksumMismatch)?;
@@ -42,7 +42,7 @@ fn ephemeral_merkle_root_returns_the_expected_root() {
     let empty: Vec<Address> = vec![];
 
     let root = ephemeral_merkle_root(empty.iter());
-    let empty = Hasher::default().digest();
+    let empty: Bytes32 = Hasher::default().digest().into();
 
     assert_eq!(empty, root);
 
@@ -83,5 +83,5 @@ fn ephemeral_merkle_root_returns_the_expected_root() {
         .digest();
     let root_p = ephemeral_merkle_root(initial.iter());
 
-    assert_eq!(root, root_p);
+    assert_eq!(Bytes32::from(root), root_p);
 }
@@ -250,7 +250,7 @@ fn writes_to_memory_correctly() {
     ];
 
     let mut assets_sorted = assets.clone();
-    assets_sorted.as_mut_slice().sort_by(|a, b| a.0.cmp(&b.0));
+    assets_sorted.as_mut_slice().sort_by_key(|a| a.0);
 
     assert_ne!(assets_sorted, assets);
 
@@ -403,13 +403,8 @@ pub mod predicates {
                     predicate,
                     recipient: address,
                     ..
-                }) => {
-                    if !Input::is_predicate_owner_valid(address, &**predicate) {
-                        return (
-                            0,
-                            Err(PredicateVerificationFailed::InvalidOwner { index }),
-                        );
-                    }
+                }) if !Input::is_predicate_owner_valid(address, &**predicate) => {
+                    return (0, Err(PredicateVerificationFailed::InvalidOwner { index }));
                 }
                 _ => {}
             }
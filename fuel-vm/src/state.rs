@@ -18,10 +18,11 @@ pub use debug::{
 
 pub use debugger::Debugger;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 /// Resulting state of an instruction set execution.
 pub enum ExecuteState {
     /// The VM should proceed normally with the execution.
+    #[default]
     Proceed,
     /// The current context returned a [`Word`].
     Return(Word),
@@ -41,12 +42,6 @@ impl ExecuteState {
     }
 }
 
-impl Default for ExecuteState {
-    fn default() -> Self {
-        Self::Proceed
-    }
-}
-
 impl From<DebugEval> for ExecuteState {
     fn from(d: DebugEval) -> Self {
         Self::DebugEvent(d)
@@ -663,7 +663,7 @@ impl InterpreterStorage for MemoryStorage {
     }
 
     fn block_hash(&self, block_height: BlockHeight) -> Result<Bytes32, Self::DataError> {
-        Ok(Hasher::hash(block_height.to_be_bytes()))
+        Ok(Hasher::hash(block_height.to_be_bytes()).into())
     }
 
     fn coinbase(&self) -> Result<ContractId, Self::DataError> {
@@ -218,7 +218,7 @@ fn state_read_write() {
     let call_data_offset = call_data_offset as Word;
 
     // Key and value to be added
-    let key = Hasher::hash(b"some key");
+    let key: Bytes32 = Hasher::hash(b"some key").into();
     let val: Word = 150;
 
     // Script data containing the call arguments (contract, a, b) and (key, value)
@@ -352,7 +352,11 @@ fn ldc__load_external_contract_code() {
     if let Receipt::LogData { digest, .. } = receipts.first().expect("No receipt") {
         let mut code = program.into_inner();
         code.extend([0; 4]);
-        assert_eq!(digest, &Hasher::hash(&code), "Loaded code digest incorrect");
+        assert_eq!(
+            digest,
+            &Bytes32::from(Hasher::hash(&code)),
+            "Loaded code digest incorrect"
+        );
     } else {
         panic!("Script did not return a value");
     }
@@ -1463,7 +1467,7 @@ fn check_receipts_for_program_call(
     let call_data_offset = call_data_offset as Word;
 
     // Key and value to be added
-    let key = Hasher::hash(b"some key");
+    let key: Bytes32 = Hasher::hash(b"some key").into();
     let val: Word = 150;
 
     // Script data containing the call arguments (contract_id, a, b) and (key, value)
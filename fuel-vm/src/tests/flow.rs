@@ -442,7 +442,7 @@ fn revert() {
     let call_data_offset = call_data_offset as Word;
 
     // Key and value to be added
-    let key = Hasher::hash(b"some key");
+    let key: Bytes32 = Hasher::hash(b"some key").into();
     let val: Word = 150;
 
     // Script data containing the call arguments (contract, a, b) and (key, value)
@@ -241,7 +241,7 @@ fn metadata() {
         .expect("IsCallerExternal should set $rA as boolean flag");
     assert_eq!(0, ra);
 
-    let contract_call = Hasher::hash(contract_id.as_ref());
+    let contract_call = Bytes32::from(Hasher::hash(contract_id.as_ref()));
     let digest = receipts[4]
         .digest()
         .expect("GetCaller should return contract Id");
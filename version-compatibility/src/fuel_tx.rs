@@ -31,6 +31,8 @@ fn latest_can_deserialize_previous_tx_pointer() {
     for idx in 0..=u16::MAX {
         // Given
         let tx_pointer = fuel_tx_0_59_1::TxPointer::new(1u32.into(), idx);
+        // The index is a `u32` when `u32-tx-pointer` is enabled.
+        #[allow(clippy::useless_conversion)]
         let expected = latest_fuel_tx::TxPointer::new(1u32.into(), idx.into());
         let bytes_expected = postcard::to_allocvec(&expected).unwrap();
         let str_expected = format!("{}", expected);